@@ -0,0 +1,326 @@
+use std::{
+    env,
+    path::PathBuf,
+    process,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use aoc::registry;
+use common::{
+    bench::{self, BenchResult, BenchSummary, History, Run, TrackingAllocator},
+    input::InputSource,
+    pool::Pool,
+    runner::{DayResult, Format, Parts, Summary},
+    solution::{print_events, traced},
+    trace::{Event, Level},
+};
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+static USAGE: &str = "Usage:
+    aoc run <day> [--part 1|2] [--input PATH|-] [--format text|json] [--trace summary|steps]
+    aoc run --all [--format text|json] [--trace summary|steps] [--jobs N] [--memory MB]
+    aoc bench <day> [--iterations N] [--input PATH|-] [--history PATH] [--threshold PERCENT]
+    aoc bench --all [--iterations N] [--history PATH] [--threshold PERCENT]
+    aoc gen <day> [--size N] [--seed S]";
+
+// Checks that exactly one of a day or --all was given, and that an input path is only given for a single day
+fn check_days(day: Option<u8>, all: bool, input: &Option<PathBuf>) -> Result<(), String> {
+    match (day, all) {
+        (Some(_), true) => Err("Expected either a day or --all, not both".to_string()),
+        (None, false) => Err("Expected a day or --all".to_string()),
+        (None, true) if input.is_some() => {
+            Err("--input can only be used with a single day".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn parse_day(arg: &str) -> Result<u8, String> {
+    match arg.parse::<u8>() {
+        Ok(d) if (1..=25).contains(&d) => Ok(d),
+        _ => Err(format!("Invalid day: {}", arg)),
+    }
+}
+
+struct RunArgs {
+    day: Option<u8>,
+    parts: Parts,
+    input: Option<PathBuf>,
+    format: Format,
+    trace: Option<Level>,
+    pool: Pool,
+}
+
+fn parse_run_args(args: &[String]) -> Result<RunArgs, String> {
+    let mut day = None;
+    let mut all = false;
+    let mut parts = Parts::Both;
+    let mut input = None;
+    let mut format = Format::Text;
+    let mut trace = None;
+    let mut jobs = 1;
+    let mut memory_mb = 1024;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--jobs" => match args.next().map(|s| s.parse::<usize>()) {
+                Some(Ok(n)) if n > 0 => jobs = n,
+                _ => return Err("Expected a positive number of jobs".to_string()),
+            },
+            "--memory" => match args.next().map(|s| s.parse::<usize>()) {
+                Some(Ok(mb)) => memory_mb = mb,
+                _ => return Err("Expected a number of megabytes after --memory".to_string()),
+            },
+            "--part" => match args.next().map(|s| s.as_str()) {
+                Some("1") => parts = Parts::One,
+                Some("2") => parts = Parts::Two,
+                _ => return Err("Expected --part 1 or --part 2".to_string()),
+            },
+            "--input" => match args.next() {
+                Some(path) => input = Some(PathBuf::from(path)),
+                None => return Err("Expected a path after --input".to_string()),
+            },
+            "--format" => match args.next() {
+                Some(name) => format = name.parse()?,
+                None => return Err("Expected --format text or --format json".to_string()),
+            },
+            "--trace" => match args.next() {
+                Some(level) => trace = Some(level.parse()?),
+                None => return Err("Expected --trace summary or --trace steps".to_string()),
+            },
+            _ => day = Some(parse_day(arg)?),
+        }
+    }
+
+    check_days(day, all, &input)?;
+
+    Ok(RunArgs {
+        day,
+        parts,
+        input,
+        format,
+        trace,
+        pool: Pool::new(jobs).memory_limit(memory_mb << 20),
+    })
+}
+
+// Runs the requested days on the pool, keeping their results, and the events traced while solving them, in order
+fn run(args: &RunArgs) -> Vec<(DayResult, Vec<Event>)> {
+    let days: Vec<_> = registry::days()
+        .into_iter()
+        .filter(|day| args.day.is_none_or(|d| d == day.day))
+        .map(|day| (day.memory, day))
+        .collect();
+
+    args.pool.run(days, |day| {
+        traced(args.trace, || {
+            match InputSource::new(day.day).path(args.input.clone()).read() {
+                Ok(input) => day.run(&input, args.parts),
+                Err(err) => DayResult::failed(day.day, err.to_string()),
+            }
+        })
+    })
+}
+
+struct BenchArgs {
+    day: Option<u8>,
+    iterations: usize,
+    input: Option<PathBuf>,
+    history: PathBuf,
+    threshold: f64,
+}
+
+fn parse_bench_args(args: &[String]) -> Result<BenchArgs, String> {
+    let mut day = None;
+    let mut all = false;
+    let mut iterations = 5;
+    let mut input = None;
+    let mut history = bench::default_history_path();
+    let mut threshold = 0.1;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--iterations" => match args.next().map(|s| s.parse::<usize>()) {
+                Some(Ok(n)) if n > 0 => iterations = n,
+                _ => return Err("Expected a positive number of iterations".to_string()),
+            },
+            "--input" => match args.next() {
+                Some(path) => input = Some(PathBuf::from(path)),
+                None => return Err("Expected a path after --input".to_string()),
+            },
+            "--history" => match args.next() {
+                Some(path) => history = PathBuf::from(path),
+                None => return Err("Expected a path after --history".to_string()),
+            },
+            "--threshold" => match args.next().map(|s| s.parse::<f64>()) {
+                Some(Ok(percent)) if percent >= 0.0 => threshold = percent / 100.0,
+                _ => return Err("Expected a threshold percentage".to_string()),
+            },
+            _ => day = Some(parse_day(arg)?),
+        }
+    }
+
+    check_days(day, all, &input)?;
+
+    Ok(BenchArgs {
+        day,
+        iterations,
+        input,
+        history,
+        threshold,
+    })
+}
+
+// Benchmarks the requested days, comparing them against and then adding them to the history.  Returns whether any
+// stage regressed.
+fn bench(args: BenchArgs) -> Result<bool, String> {
+    let mut history = History::load(&args.history)?;
+
+    let results: Vec<BenchResult> = registry::days()
+        .iter()
+        .filter(|day| args.day.is_none_or(|d| d == day.day))
+        .map(
+            |day| match InputSource::new(day.day).path(args.input.clone()).read() {
+                Ok(input) => day.bench(&input, args.iterations),
+                Err(err) => BenchResult {
+                    day: day.day,
+                    outcome: Err(err.to_string()),
+                },
+            },
+        )
+        .collect();
+
+    let summary = BenchSummary {
+        results: &results,
+        history: &history,
+        threshold: args.threshold,
+    };
+    print!("{}", summary);
+    let regressed = summary.regressions() > 0;
+
+    history.runs.push(Run::new(args.iterations, &results));
+    history.save(&args.history)?;
+
+    Ok(regressed)
+}
+
+struct GenArgs {
+    day: u8,
+    size: Option<usize>,
+    seed: u64,
+}
+
+fn parse_gen_args(args: &[String]) -> Result<GenArgs, String> {
+    let mut day = None;
+    let mut size = None;
+    let mut seed = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--size" => match args.next().map(|s| s.parse::<usize>()) {
+                Some(Ok(n)) => size = Some(n),
+                _ => return Err("Expected a size after --size".to_string()),
+            },
+            "--seed" => match args.next().map(|s| s.parse::<u64>()) {
+                Some(Ok(n)) => seed = Some(n),
+                _ => return Err("Expected a number after --seed".to_string()),
+            },
+            _ => day = Some(parse_day(arg)?),
+        }
+    }
+
+    // Without a seed each run gives a different input, and the seed is reported so that it can be made again
+    let seed = seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+    });
+
+    Ok(GenArgs {
+        day: day.ok_or("Expected a day")?,
+        size,
+        seed,
+    })
+}
+
+// Writes a generated input to stdout, so that it can be piped into `aoc run`, and the answers known for it to stderr
+fn gen(args: GenArgs) -> Result<(), String> {
+    let days = registry::days();
+    let generated = days
+        .iter()
+        .find(|day| day.day == args.day)
+        .and_then(|day| day.generate(args.size, args.seed))
+        .ok_or_else(|| format!("Day {} can't generate inputs", args.day))?;
+
+    println!("{}", generated.input);
+
+    let known = |answer: &Option<String>| answer.clone().unwrap_or_else(|| "unknown".to_string());
+    eprintln!("seed: {}", args.seed);
+    eprintln!("part 1: {}", known(&generated.part_1));
+    eprintln!("part 2: {}", known(&generated.part_2));
+
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.split_first() {
+        Some((command, rest)) if command == "run" => match parse_run_args(rest) {
+            Ok(run_args) => {
+                let (results, events): (Vec<_>, Vec<_>) = run(&run_args).into_iter().unzip();
+                print_events(&events.concat(), run_args.format);
+                match run_args.format {
+                    Format::Text => print!("{}", Summary(&results)),
+                    // One object per line, so that each day can be consumed on its own
+                    Format::Json => {
+                        for result in &results {
+                            println!("{}", result.to_json());
+                        }
+                    }
+                }
+            }
+            Err(err) => {
+                eprintln!("{}\n\n{}", err, USAGE);
+                process::exit(2);
+            }
+        },
+        Some((command, rest)) if command == "bench" => match parse_bench_args(rest) {
+            Ok(bench_args) => match bench(bench_args) {
+                Ok(false) => {}
+                Ok(true) => process::exit(1),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    process::exit(1);
+                }
+            },
+            Err(err) => {
+                eprintln!("{}\n\n{}", err, USAGE);
+                process::exit(2);
+            }
+        },
+        Some((command, rest)) if command == "gen" => match parse_gen_args(rest) {
+            Ok(gen_args) => {
+                if let Err(err) = gen(gen_args) {
+                    eprintln!("{}", err);
+                    process::exit(1);
+                }
+            }
+            Err(err) => {
+                eprintln!("{}\n\n{}", err, USAGE);
+                process::exit(2);
+            }
+        },
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use std::panic;
+
+use aoc::registry;
+use common::{manifest, pool::Pool};
+
+// Solves every input in the manifest, failing with a diff of every answer that has changed
+#[test]
+fn test_answers() {
+    let path = manifest::default_path();
+    let entries = manifest::load(&path).unwrap();
+    let base = path.parent().unwrap();
+    let days = registry::days();
+
+    // The slowest days take several seconds unoptimised, so the inputs are checked in parallel, though without
+    // allocating every large table at once
+    let tasks = entries
+        .iter()
+        .map(|entry| {
+            let day = days.iter().find(|day| day.day == entry.day);
+            (day.map_or(0, |day| day.memory), (entry, day))
+        })
+        .collect();
+    let failures: Vec<String> = Pool::available()
+        .memory_limit(1 << 30)
+        .run(tasks, |(entry, day)| {
+            let mismatches = match day {
+                Some(day) => panic::catch_unwind(|| manifest::check(entry, base, day))
+                    .map(|mismatches| mismatches.iter().map(|m| m.to_string()).collect())
+                    .unwrap_or_else(|_| vec!["panicked".to_string()]),
+                None => vec![format!("day {} isn't registered", entry.day)],
+            };
+
+            mismatches
+                .into_iter()
+                .map(|mismatch| {
+                    format!("day {} {}: {}", entry.day, entry.input.display(), mismatch)
+                })
+                .collect::<Vec<_>>()
+        })
+        .into_iter()
+        .flatten()
+        .collect();
+
+    assert!(
+        failures.is_empty(),
+        "{} answer(s) changed:\n\n{}",
+        failures.len(),
+        failures.join("\n\n")
+    );
+}
@@ -0,0 +1,43 @@
+use aoc::registry;
+use common::runner::Parts;
+
+// Solves generated inputs, both tiny and full sized, for every day which can make them, checking the answers that
+// were planted in them
+#[test]
+fn test_generated() {
+    let mut failures = Vec::new();
+
+    for day in registry::days().iter().filter(|day| day.can_generate()) {
+        for size in [None, Some(1), Some(30)].iter() {
+            for seed in 0..5 {
+                let generated = day.generate(*size, seed).unwrap();
+                let solved = match day.run(&generated.input, Parts::Both).outcome {
+                    Ok(solved) => solved,
+                    Err(reason) => {
+                        failures.push(format!("day {} seed {}: {}", day.day, seed, reason));
+                        continue;
+                    }
+                };
+
+                let expected = [&generated.part_1, &generated.part_2];
+                let found = [&solved.part_1, &solved.part_2];
+                for (part, (expected, found)) in (1..).zip(expected.iter().zip(found.iter())) {
+                    let found = found.as_ref().map(|found| found.answer.to_string());
+                    if expected.is_some() && **expected != found {
+                        failures.push(format!(
+                            "day {} part {} with size {:?} and seed {}: expected {:?}, found {:?}",
+                            day.day, part, size, seed, expected, found
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} generated answer(s) were wrong:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
@@ -0,0 +1,268 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use crate::trace;
+
+// A life-like rule, giving the numbers of active neighbours for which an inactive cell becomes active, and for which
+// an active cell stays active
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Rule {
+    born: u128,
+    survives: u128,
+}
+
+impl Rule {
+    pub fn new(born: &[usize], survives: &[usize]) -> Rule {
+        let mask = |counts: &[usize]| {
+            counts.iter().fold(0, |mask, &count| {
+                assert!(count < 128, "a rule can't count {} neighbours", count);
+                mask | 1 << count
+            })
+        };
+
+        Rule {
+            born: mask(born),
+            survives: mask(survives),
+        }
+    }
+
+    pub fn is_active(&self, active: bool, neighbours: usize) -> bool {
+        let mask = if active { self.survives } else { self.born };
+        neighbours < 128 && mask & 1 << neighbours != 0
+    }
+}
+
+// A cellular automaton which only tracks its active cells, so that it can grow without bound.  `neighbours` gives
+// every neighbour of a cell, each once and not including the cell itself.
+#[derive(Debug, Clone)]
+pub struct Automaton<P, N> {
+    active: HashSet<P>,
+    neighbours: N,
+    rule: Rule,
+    universe: Option<HashSet<P>>,
+    generation: usize,
+}
+
+impl<P, N, I> Automaton<P, N>
+where
+    P: Copy + Eq + Hash,
+    N: Fn(P) -> I,
+    I: IntoIterator<Item = P>,
+{
+    pub fn new(active: impl IntoIterator<Item = P>, neighbours: N, rule: Rule) -> Automaton<P, N> {
+        Automaton {
+            active: active.into_iter().collect(),
+            neighbours,
+            rule,
+            universe: None,
+            generation: 0,
+        }
+    }
+
+    // Limits the automaton to a fixed set of cells.  This is needed for a rule under which a cell with no active
+    // neighbours can become active, as otherwise the whole of an unbounded space would come to life.
+    pub fn within(mut self, universe: impl IntoIterator<Item = P>) -> Automaton<P, N> {
+        let universe: HashSet<P> = universe.into_iter().collect();
+        self.active.retain(|p| universe.contains(p));
+        self.universe = Some(universe);
+        self
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn population(&self) -> usize {
+        self.active.len()
+    }
+
+    pub fn active(&self) -> &HashSet<P> {
+        &self.active
+    }
+
+    // The cells the automaton is limited to, if it's bounded
+    pub fn universe(&self) -> Option<&HashSet<P>> {
+        self.universe.as_ref()
+    }
+
+    pub fn is_active(&self, p: &P) -> bool {
+        self.active.contains(p)
+    }
+
+    // Advances a generation, returning whether any cell changed
+    pub fn step(&mut self) -> bool {
+        let mut counts: HashMap<P, usize> = HashMap::new();
+        for &p in self.active.iter() {
+            for q in (self.neighbours)(p) {
+                *counts.entry(q).or_insert(0) += 1;
+            }
+        }
+
+        let next: HashSet<P> = match &self.universe {
+            Some(universe) => universe
+                .iter()
+                .filter(|p| {
+                    let count = counts.get(p).copied().unwrap_or(0);
+                    self.rule.is_active(self.active.contains(p), count)
+                })
+                .copied()
+                .collect(),
+            None => {
+                assert!(
+                    !self.rule.is_active(false, 0),
+                    "an unbounded automaton can't bring isolated cells to life"
+                );
+
+                // An active cell with no active neighbours won't have been counted
+                let isolated = self
+                    .active
+                    .iter()
+                    .filter(|p| !counts.contains_key(p))
+                    .map(|&p| (p, 0));
+                counts
+                    .iter()
+                    .map(|(&p, &count)| (p, count))
+                    .chain(isolated)
+                    .filter(|&(p, count)| self.rule.is_active(self.active.contains(&p), count))
+                    .map(|(p, _)| p)
+                    .collect()
+            }
+        };
+
+        self.generation += 1;
+        let changed = next != self.active;
+        self.active = next;
+        trace::step("step", |fields| {
+            fields
+                .with("generation", self.generation)
+                .with("population", self.population())
+        });
+
+        changed
+    }
+
+    pub fn run(&mut self, generations: usize) -> usize {
+        for _ in 0..generations {
+            self.step();
+        }
+        trace::summary("finished", |fields| {
+            fields
+                .with("generation", self.generation)
+                .with("population", self.population())
+        });
+
+        self.population()
+    }
+
+    // Steps until a generation leaves every cell as it was, returning the population.  This will never return for
+    // an automaton which doesn't settle down.
+    pub fn run_until_stable(&mut self) -> usize {
+        while self.step() {}
+        trace::summary("stable", |fields| {
+            fields
+                .with("generation", self.generation)
+                .with("population", self.population())
+        });
+
+        self.population()
+    }
+}
+
+// Every point adjacent to `p`, including diagonally, in any number of dimensions
+pub fn moore<const D: usize>(p: [i64; D]) -> Moore<D> {
+    Moore {
+        p,
+        n: 0,
+        count: 3usize.pow(D as u32),
+    }
+}
+
+// The points adjacent to a point, as a type which can be named in the type of an automaton using them
+pub struct Moore<const D: usize> {
+    p: [i64; D],
+    n: usize,
+    count: usize,
+}
+
+impl<const D: usize> Iterator for Moore<D> {
+    type Item = [i64; D];
+
+    fn next(&mut self) -> Option<[i64; D]> {
+        while self.n < self.count {
+            let mut n = self.n;
+            self.n += 1;
+
+            let mut q = self.p;
+            for coordinate in q.iter_mut() {
+                *coordinate += (n % 3) as i64 - 1;
+                n /= 3;
+            }
+            if q != self.p {
+                return Some(q);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule() {
+        let life = Rule::new(&[3], &[2, 3]);
+
+        assert!(life.is_active(false, 3));
+        assert!(!life.is_active(false, 2));
+        assert!(life.is_active(true, 2));
+        assert!(!life.is_active(true, 4));
+        assert!(!life.is_active(true, 200));
+    }
+
+    #[test]
+    fn test_moore() {
+        assert_eq!(moore([0]).collect::<Vec<_>>(), vec![[-1], [1]]);
+        assert_eq!(moore([5, 5, 5]).count(), 26);
+        assert_eq!(moore([0, 0, 0, 0]).count(), 80);
+    }
+
+    #[test]
+    fn test_blinker() {
+        let mut automaton = Automaton::new(
+            vec![[0, -1], [0, 0], [0, 1]],
+            moore,
+            Rule::new(&[3], &[2, 3]),
+        );
+
+        assert!(automaton.step());
+        assert_eq!(automaton.generation(), 1);
+        let mut active: Vec<_> = automaton.active().iter().copied().collect();
+        active.sort_unstable();
+        assert_eq!(active, vec![[-1, 0], [0, 0], [1, 0]]);
+
+        assert_eq!(automaton.run(3), 3);
+        assert_eq!(automaton.generation(), 4);
+        assert!(automaton.is_active(&[0, 1]));
+    }
+
+    #[test]
+    fn test_within() {
+        // Every cell comes to life, and then those with two neighbours die
+        let mut automaton = Automaton::new(
+            vec![],
+            |p: i64| vec![p - 1, p + 1],
+            Rule::new(&[0], &[0, 1]),
+        )
+        .within(0..4);
+
+        assert!(automaton.step());
+        assert_eq!(automaton.population(), 4);
+        assert_eq!(automaton.run_until_stable(), 2);
+        assert_eq!(automaton.generation(), 3);
+        assert!(automaton.is_active(&0) && automaton.is_active(&3));
+    }
+}
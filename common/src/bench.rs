@@ -0,0 +1,463 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Solution;
+
+// A global allocator which keeps track of how much memory is allocated, so that the peak usage of each stage can be
+// measured.  Binaries opt in with `#[global_allocator]`; without it every peak is reported as zero.
+pub struct TrackingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+fn record_alloc(size: usize) {
+    let allocated = ALLOCATED.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(allocated, Ordering::Relaxed);
+}
+
+fn record_dealloc(size: usize) {
+    ALLOCATED.fetch_sub(size, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record_dealloc(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record_dealloc(layout.size());
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+// Runs `f`, returning its result along with how long it took and the most memory it had allocated at once, over and
+// above whatever was already allocated beforehand
+fn measure<T>(f: impl FnOnce() -> T) -> (T, Duration, usize) {
+    let baseline = ALLOCATED.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+
+    let peak = PEAK.load(Ordering::Relaxed).saturating_sub(baseline);
+
+    (result, elapsed, peak)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub max_ns: u64,
+    pub peak_memory_bytes: u64,
+}
+
+impl Stats {
+    fn new(samples: &mut [Duration], peak_memory: usize) -> Stats {
+        samples.sort();
+
+        let mid = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[mid - 1] + samples[mid]) / 2
+        } else {
+            samples[mid]
+        };
+
+        Stats {
+            min_ns: samples[0].as_nanos() as u64,
+            median_ns: median.as_nanos() as u64,
+            max_ns: samples[samples.len() - 1].as_nanos() as u64,
+            peak_memory_bytes: peak_memory as u64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stage::Parse => write!(f, "parse"),
+            Stage::Part1 => write!(f, "part 1"),
+            Stage::Part2 => write!(f, "part 2"),
+        }
+    }
+}
+
+static STAGES: [Stage; 3] = [Stage::Parse, Stage::Part1, Stage::Part2];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DayBench {
+    pub day: u8,
+    pub parse: Stats,
+    pub part_1: Stats,
+    pub part_2: Stats,
+}
+
+impl DayBench {
+    pub fn stage(&self, stage: Stage) -> &Stats {
+        match stage {
+            Stage::Parse => &self.parse,
+            Stage::Part1 => &self.part_1,
+            Stage::Part2 => &self.part_2,
+        }
+    }
+}
+
+// Parses and solves both parts `iterations` times, timing each stage separately
+pub fn bench<S: Solution>(input: &str, iterations: usize) -> Result<DayBench, String> {
+    let mut samples = [vec![], vec![], vec![]];
+    let mut peaks = [0; 3];
+
+    for _ in 0..iterations.max(1) {
+        let (parsed, elapsed, peak) = measure(|| S::parse(input));
+        let parsed = parsed.map_err(|err| format!("unable to parse input: {}", err))?;
+        samples[0].push(elapsed);
+        peaks[0] = peaks[0].max(peak);
+
+        let (_, elapsed, peak) = measure(|| S::part1(&parsed));
+        samples[1].push(elapsed);
+        peaks[1] = peaks[1].max(peak);
+
+        let (_, elapsed, peak) = measure(|| S::part2(&parsed));
+        samples[2].push(elapsed);
+        peaks[2] = peaks[2].max(peak);
+    }
+
+    let [parse, part_1, part_2] = &mut samples;
+    Ok(DayBench {
+        day: S::DAY,
+        parse: Stats::new(parse, peaks[0]),
+        part_1: Stats::new(part_1, peaks[1]),
+        part_2: Stats::new(part_2, peaks[2]),
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct BenchResult {
+    pub day: u8,
+    pub outcome: Result<DayBench, String>,
+}
+
+// One invocation of the benchmarks, as recorded in the history file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Run {
+    pub timestamp: u64,
+    pub iterations: usize,
+    pub days: Vec<DayBench>,
+}
+
+impl Run {
+    pub fn new(iterations: usize, results: &[BenchResult]) -> Run {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+
+        Run {
+            timestamp,
+            iterations,
+            days: results
+                .iter()
+                .filter_map(|result| result.outcome.as_ref().ok().cloned())
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct History {
+    pub runs: Vec<Run>,
+}
+
+// The history file used when none is given, in the workspace's bench directory
+pub fn default_history_path() -> PathBuf {
+    let mut path_buf = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path_buf.pop();
+    path_buf.push("bench");
+    path_buf.push("history.json");
+    path_buf
+}
+
+impl History {
+    // A missing history file is treated as an empty history
+    pub fn load(path: &Path) -> Result<History, String> {
+        match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)
+                .map_err(|err| format!("unable to parse {}: {}", path.display(), err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(History::default()),
+            Err(err) => Err(format!("unable to read {}: {}", path.display(), err)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let write = || -> io::Result<()> {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let json = serde_json::to_string_pretty(self)?;
+            fs::write(path, json + "\n")
+        };
+
+        write().map_err(|err| format!("unable to write {}: {}", path.display(), err))
+    }
+
+    // The most recent previous benchmark of a day
+    pub fn previous(&self, day: u8) -> Option<&DayBench> {
+        self.runs
+            .iter()
+            .rev()
+            .find_map(|run| run.days.iter().find(|bench| bench.day == day))
+    }
+}
+
+// Differences in median below this are ignored, as they are dominated by noise rather than by the code being run
+static NOISE_FLOOR: Duration = Duration::from_micros(100);
+
+// The change in median time of a stage, if it is slower than before by more than `threshold` (a fraction, so 0.1 is
+// 10%)
+pub fn regression(previous: &Stats, current: &Stats, threshold: f64) -> Option<f64> {
+    let change = current.median_ns as f64 / previous.median_ns.max(1) as f64 - 1.0;
+    let difference = current.median_ns.saturating_sub(previous.median_ns);
+
+    if change > threshold && difference > NOISE_FLOOR.as_nanos() as u64 {
+        Some(change)
+    } else {
+        None
+    }
+}
+
+pub struct BenchSummary<'a> {
+    pub results: &'a [BenchResult],
+    pub history: &'a History,
+    pub threshold: f64,
+}
+
+impl BenchSummary<'_> {
+    pub fn regressions(&self) -> usize {
+        self.results
+            .iter()
+            .filter_map(|result| result.outcome.as_ref().ok())
+            .map(|bench| {
+                STAGES
+                    .iter()
+                    .filter(|&&stage| self.compare(bench, stage).1)
+                    .count()
+            })
+            .sum()
+    }
+
+    // The change against the previous run, formatted, and whether it is a regression
+    fn compare(&self, bench: &DayBench, stage: Stage) -> (String, bool) {
+        let previous = match self.history.previous(bench.day) {
+            Some(previous) => previous.stage(stage),
+            None => return (String::new(), false),
+        };
+        let current = bench.stage(stage);
+
+        let change = current.median_ns as f64 / previous.median_ns.max(1) as f64 - 1.0;
+        match regression(previous, current, self.threshold) {
+            Some(_) => (format!("{:+.1}% regression", change * 100.0), true),
+            None => (format!("{:+.1}%", change * 100.0), false),
+        }
+    }
+}
+
+fn format_nanos(nanos: u64) -> String {
+    crate::runner::format_duration(Duration::from_nanos(nanos))
+}
+
+fn format_bytes(bytes: u64) -> String {
+    if bytes < 1 << 10 {
+        format!("{}B", bytes)
+    } else if bytes < 1 << 20 {
+        format!("{:.1}KiB", bytes as f64 / (1 << 10) as f64)
+    } else {
+        format!("{:.1}MiB", bytes as f64 / (1 << 20) as f64)
+    }
+}
+
+impl fmt::Display for BenchSummary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = ["Day", "Stage", "Min", "Median", "Max", "Memory", "Change"]
+            .map(|heading| heading.to_string());
+
+        let mut rows: Vec<(u8, Result<[String; 7], String>)> = vec![];
+        for result in self.results {
+            match &result.outcome {
+                Ok(bench) => {
+                    for (idx, &stage) in STAGES.iter().enumerate() {
+                        let stats = bench.stage(stage);
+                        let day = if idx == 0 {
+                            bench.day.to_string()
+                        } else {
+                            String::new()
+                        };
+                        rows.push((
+                            bench.day,
+                            Ok([
+                                day,
+                                stage.to_string(),
+                                format_nanos(stats.min_ns),
+                                format_nanos(stats.median_ns),
+                                format_nanos(stats.max_ns),
+                                format_bytes(stats.peak_memory_bytes),
+                                self.compare(bench, stage).0,
+                            ]),
+                        ));
+                    }
+                }
+                Err(reason) => rows.push((result.day, Err(reason.clone()))),
+            }
+        }
+
+        let mut widths = [0; 7];
+        for row in
+            std::iter::once(&header).chain(rows.iter().filter_map(|(_, row)| row.as_ref().ok()))
+        {
+            for (width, cell) in widths.iter_mut().zip(row.iter()) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let format_row = |row: &[String]| {
+            row.iter()
+                .zip(widths.iter())
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join(" | ")
+                .trim_end()
+                .to_string()
+        };
+
+        writeln!(f, "{}", format_row(&header))?;
+        for (day, row) in &rows {
+            match row {
+                Ok(row) => writeln!(f, "{}", format_row(row))?,
+                Err(reason) => {
+                    writeln!(f, "{:<width$} | error: {}", day, reason, width = widths[0])?
+                }
+            }
+        }
+
+        match self.regressions() {
+            0 => Ok(()),
+            n => writeln!(
+                f,
+                "{} stage{} slower than the previous run by more than {:.0}%",
+                n,
+                if n == 1 { "" } else { "s" },
+                self.threshold * 100.0
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(median_ns: u64) -> Stats {
+        Stats {
+            min_ns: median_ns,
+            median_ns,
+            max_ns: median_ns,
+            peak_memory_bytes: 0,
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut samples: Vec<_> = [5, 1, 3]
+            .iter()
+            .map(|&ms| Duration::from_millis(ms))
+            .collect();
+        let stats = Stats::new(&mut samples, 64);
+        assert_eq!(
+            (stats.min_ns, stats.median_ns, stats.max_ns),
+            (1_000_000, 3_000_000, 5_000_000)
+        );
+
+        let mut samples: Vec<_> = [4, 1, 2, 8]
+            .iter()
+            .map(|&ms| Duration::from_millis(ms))
+            .collect();
+        assert_eq!(Stats::new(&mut samples, 0).median_ns, 3_000_000);
+    }
+
+    #[test]
+    fn test_regression() {
+        // 20% slower, and well above the noise floor
+        assert!(regression(&stats(10_000_000), &stats(12_000_000), 0.1).is_some());
+        assert!(regression(&stats(10_000_000), &stats(12_000_000), 0.25).is_none());
+        assert!(regression(&stats(12_000_000), &stats(10_000_000), 0.1).is_none());
+
+        // Twice as slow, but only by a few microseconds
+        assert!(regression(&stats(2_000), &stats(4_000), 0.1).is_none());
+    }
+
+    #[test]
+    fn test_history() {
+        let bench = |day, median_ns| DayBench {
+            day,
+            parse: stats(median_ns),
+            part_1: stats(median_ns),
+            part_2: stats(median_ns),
+        };
+        let history = History {
+            runs: vec![
+                Run {
+                    timestamp: 1,
+                    iterations: 5,
+                    days: vec![bench(1, 100), bench(2, 200)],
+                },
+                Run {
+                    timestamp: 2,
+                    iterations: 5,
+                    days: vec![bench(1, 300)],
+                },
+            ],
+        };
+
+        assert_eq!(history.previous(1), Some(&bench(1, 300)));
+        assert_eq!(history.previous(2), Some(&bench(2, 200)));
+        assert_eq!(history.previous(3), None);
+
+        let json = serde_json::to_string(&history).unwrap();
+        assert_eq!(serde_json::from_str::<History>(&json).unwrap(), history);
+    }
+}
@@ -0,0 +1,149 @@
+use std::{error, fmt, str::FromStr};
+
+// An error found while parsing puzzle input.  Lines and columns are 1-based, and are relative to the string that was
+// being parsed: an error found in a single line is re-expressed relative to the whole input with `within`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub snippet: String,
+    pub expected: String,
+}
+
+// Finds the line and column at which `fragment` starts, if it is a slice of `input`
+fn position(input: &str, fragment: &str) -> Option<(usize, usize)> {
+    let start = input.as_ptr() as usize;
+    let fragment_start = fragment.as_ptr() as usize;
+    if fragment_start < start || fragment_start + fragment.len() > start + input.len() {
+        return None;
+    }
+
+    let preceding = &input[..fragment_start - start];
+    let line = preceding.matches('\n').count() + 1;
+    let column = match preceding.rfind('\n') {
+        Some(idx) => preceding[idx + 1..].chars().count() + 1,
+        None => preceding.chars().count() + 1,
+    };
+
+    Some((line, column))
+}
+
+impl ParseError {
+    // An error at `snippet`, which should be a slice of `input` so that its position can be found.  Otherwise the
+    // error is placed at the start of `input`.
+    pub fn new(input: &str, snippet: &str, expected: impl Into<String>) -> ParseError {
+        let (line, column) = position(input, snippet).unwrap_or((1, 1));
+
+        ParseError {
+            line,
+            column,
+            snippet: snippet.to_string(),
+            expected: expected.into(),
+        }
+    }
+
+    pub fn end_of_input(input: &str, expected: impl Into<String>) -> ParseError {
+        ParseError::new(input, &input[input.len()..], expected)
+    }
+
+    // Re-expresses an error found while parsing `fragment`, a slice of `input`, relative to `input`
+    pub fn within(self, input: &str, fragment: &str) -> ParseError {
+        match position(input, fragment) {
+            Some((line, column)) => ParseError {
+                line: self.line + line - 1,
+                column: if self.line == 1 {
+                    self.column + column - 1
+                } else {
+                    self.column
+                },
+                ..self
+            },
+            None => self,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, found ",
+            self.line, self.column, self.expected
+        )?;
+        if self.snippet.is_empty() {
+            write!(f, "end of input")
+        } else {
+            write!(f, "{:?}", self.snippet)
+        }
+    }
+}
+
+impl error::Error for ParseError {}
+
+// Parses `token`, a slice of `input`, as a number
+pub fn parse_number<T: FromStr>(input: &str, token: &str) -> Result<T, ParseError> {
+    token
+        .parse()
+        .map_err(|_| ParseError::new(input, token, "a number"))
+}
+
+// Parses each line of `input` in turn, re-expressing any error relative to the whole input
+pub fn parse_lines<T>(
+    input: &str,
+    mut parse: impl FnMut(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .map(|line| parse(line).map_err(|err| err.within(input, line)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position() {
+        let input = "abc\ndéf\nghi";
+
+        let err = ParseError::new(input, &input[7..8], "a digit");
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.snippet, "f");
+
+        let err = ParseError::end_of_input(input, "a digit");
+        assert_eq!((err.line, err.column), (3, 4));
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 4: expected a digit, found end of input"
+        );
+    }
+
+    #[test]
+    fn test_unrelated_snippet() {
+        let err = ParseError::new("abc", "xyz", "a letter");
+        assert_eq!((err.line, err.column), (1, 1));
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let input = "1\n2\n3 4\n5";
+
+        let result: Result<Vec<u32>, _> = parse_lines(input, |line| parse_number(line, line));
+        let err = result.unwrap_err();
+        assert_eq!((err.line, err.column), (3, 1));
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 1: expected a number, found \"3 4\""
+        );
+
+        let result: Result<Vec<u32>, _> = parse_lines(input, |line| {
+            let token = line.split(' ').next_back().unwrap();
+            parse_number::<u32>(line, token).and_then(|n| match n {
+                4 => Err(ParseError::new(line, token, "anything but 4")),
+                n => Ok(n),
+            })
+        });
+        let err = result.unwrap_err();
+        assert_eq!((err.line, err.column), (3, 3));
+    }
+}
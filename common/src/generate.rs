@@ -0,0 +1,106 @@
+use rand::SeedableRng;
+
+pub use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+
+use crate::Solution;
+
+// A randomly generated puzzle input, along with whichever answers are known from the way it was built
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Generated {
+    pub input: String,
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+}
+
+impl Generated {
+    pub fn new(input: String) -> Generated {
+        Generated {
+            input,
+            part_1: None,
+            part_2: None,
+        }
+    }
+
+    pub fn part_1(self, answer: impl ToString) -> Generated {
+        Generated {
+            part_1: Some(answer.to_string()),
+            ..self
+        }
+    }
+
+    pub fn part_2(self, answer: impl ToString) -> Generated {
+        Generated {
+            part_2: Some(answer.to_string()),
+            ..self
+        }
+    }
+}
+
+// A day which can make up valid inputs of its own, for finding the bugs that the one real input doesn't show.  What
+// `size` measures is up to each day, e.g. the number of lines, and `DEFAULT_SIZE` matches the real input.  Known
+// answers should come from how the input was built rather than from solving it, so that they can catch the solution
+// out.
+pub trait Generator: Solution {
+    const DEFAULT_SIZE: usize;
+
+    fn generate(rng: &mut StdRng, size: usize) -> Generated;
+}
+
+// The same seed and size always give the same input
+pub fn generate<G: Generator>(size: usize, seed: u64) -> Generated {
+    G::generate(&mut StdRng::seed_from_u64(seed), size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::parse_number, Answer, ParseError};
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input = Vec<u64>;
+
+        const DAY: u8 = 1;
+
+        fn parse(input: &str) -> Result<Vec<u64>, ParseError> {
+            input.lines().map(|line| parse_number(line, line)).collect()
+        }
+
+        fn part1(input: &Vec<u64>) -> Answer {
+            input.iter().sum::<u64>().into()
+        }
+
+        fn part2(_: &Vec<u64>) -> Answer {
+            Answer::Unsolved
+        }
+    }
+
+    impl Generator for Sum {
+        const DEFAULT_SIZE: usize = 10;
+
+        fn generate(rng: &mut StdRng, size: usize) -> Generated {
+            let values: Vec<u64> = (0..size).map(|_| rng.gen_range(0..100)).collect();
+            let input = values
+                .iter()
+                .map(|value| value.to_string())
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            Generated::new(input).part_1(values.iter().sum::<u64>())
+        }
+    }
+
+    #[test]
+    fn test_generate() {
+        let generated = generate::<Sum>(5, 42);
+
+        assert_eq!(generated, generate::<Sum>(5, 42));
+        assert_ne!(generated, generate::<Sum>(5, 43));
+        assert_eq!(generated.input.lines().count(), 5);
+        assert_eq!(generated.part_2, None);
+
+        let values = Sum::parse(&generated.input).unwrap();
+        assert_eq!(Some(Sum::part1(&values).to_string()), generated.part_1);
+    }
+}
@@ -0,0 +1,389 @@
+use std::{
+    borrow::Borrow,
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    hash::Hash,
+    ops::Add,
+};
+
+// A directed graph with weighted edges.  Every node mentioned by an edge is a node of the graph, even if it has no
+// edges of its own.
+#[derive(Debug, Clone)]
+pub struct Graph<N, W> {
+    edges: HashMap<N, Vec<(N, W)>>,
+}
+
+impl<N: Eq + Hash, W> Default for Graph<N, W> {
+    fn default() -> Self {
+        Graph {
+            edges: HashMap::new(),
+        }
+    }
+}
+
+impl<N: Clone + Eq + Hash, W> Graph<N, W> {
+    pub fn new() -> Graph<N, W> {
+        Graph::default()
+    }
+
+    pub fn add_node(&mut self, u: N) {
+        self.edges.entry(u).or_default();
+    }
+
+    pub fn add_edge(&mut self, u: N, v: N, weight: W) {
+        self.add_node(v.clone());
+        self.edges.entry(u).or_default().push((v, weight));
+    }
+
+    pub fn contains<Q>(&self, u: &Q) -> bool
+    where
+        N: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.edges.contains_key(u)
+    }
+
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.edges.keys()
+    }
+
+    // The edges leaving `u`, which are empty if it isn't in the graph
+    pub fn neighbours<Q>(&self, u: &Q) -> impl Iterator<Item = (&N, &W)>
+    where
+        N: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.edges
+            .get(u)
+            .into_iter()
+            .flat_map(|vs| vs.iter().map(|(v, weight)| (v, weight)))
+    }
+
+    pub fn reverse_edges(&self) -> Graph<N, W>
+    where
+        W: Clone,
+    {
+        let mut reversed_graph = Graph::new();
+
+        for (u, vs) in self.edges.iter() {
+            reversed_graph.add_node(u.clone());
+            for (v, weight) in vs.iter() {
+                reversed_graph.add_edge(v.clone(), u.clone(), weight.clone());
+            }
+        }
+
+        reversed_graph
+    }
+
+    // Every node which can be reached from `start` by following one or more edges, so `start` is only included if
+    // it is on a cycle
+    pub fn reachable<Q>(&self, start: &Q) -> HashSet<&N>
+    where
+        N: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let mut seen: HashSet<&N> = HashSet::new();
+        let mut stack: Vec<&N> = self.neighbours(start).map(|(v, _)| v).collect();
+
+        while let Some(u) = stack.pop() {
+            if seen.insert(u) {
+                stack.extend(self.neighbours::<N>(u).map(|(v, _)| v));
+            }
+        }
+
+        seen
+    }
+
+    // Orders the nodes so that every edge goes forwards, or returns None if there is a cycle
+    pub fn topological_sort(&self) -> Option<Vec<&N>> {
+        let mut in_degrees: HashMap<&N, usize> = self.nodes().map(|u| (u, 0)).collect();
+        for (v, _) in self.edges.values().flatten() {
+            *in_degrees.get_mut(v).unwrap() += 1;
+        }
+
+        let mut sources: Vec<&N> = in_degrees
+            .iter()
+            .filter(|(_, in_degree)| **in_degree == 0)
+            .map(|(u, _)| *u)
+            .collect();
+        let mut sorted = Vec::with_capacity(self.len());
+
+        while let Some(u) = sources.pop() {
+            sorted.push(u);
+            for (v, _) in self.neighbours(u) {
+                let in_degree = in_degrees.get_mut(v).unwrap();
+                *in_degree -= 1;
+                if *in_degree == 0 {
+                    sources.push(v);
+                }
+            }
+        }
+
+        // The nodes on, or after, a cycle never run out of incoming edges
+        if sorted.len() == self.len() {
+            Some(sorted)
+        } else {
+            None
+        }
+    }
+
+    // Returns the nodes of a cycle, in order, if there is one
+    pub fn find_cycle(&self) -> Option<Vec<&N>> {
+        let mut finished: HashSet<&N> = HashSet::new();
+
+        for root in self.nodes() {
+            if finished.contains(root) {
+                continue;
+            }
+
+            // A depth first search, where the path holds each node on the stack along with its remaining edges
+            let mut path = vec![(root, self.neighbours(root))];
+            let mut on_path: HashSet<&N> = vec![root].into_iter().collect();
+
+            while let Some((_, edges)) = path.last_mut() {
+                match edges.next() {
+                    Some((v, _)) if on_path.contains(v) => {
+                        let start = path.iter().position(|(u, _)| *u == v).unwrap();
+                        return Some(path[start..].iter().map(|(u, _)| *u).collect());
+                    }
+                    Some((v, _)) if !finished.contains(v) => {
+                        on_path.insert(v);
+                        path.push((v, self.neighbours(v)));
+                    }
+                    Some(_) => {}
+                    None => {
+                        let (u, _) = path.pop().unwrap();
+                        on_path.remove(u);
+                        finished.insert(u);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    // Combines the values of everything below `start` into a value for `start`, where `f` is given each node along
+    // with its edges and the values already found for the nodes they lead to.  Each node's value is only found once,
+    // however many paths lead to it.  Returns None if a cycle can be reached from `start`.
+    pub fn fold<Q, T, F>(&self, start: &Q, mut f: F) -> Option<T>
+    where
+        N: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        T: Clone,
+        F: FnMut(&N, &[(&W, T)]) -> T,
+    {
+        let (start, _) = self.edges.get_key_value(start)?;
+        let mut values = HashMap::new();
+        let mut in_progress = HashSet::new();
+
+        self.fold_from(start, &mut f, &mut values, &mut in_progress)
+    }
+
+    // The lightest path from `start` to a node satisfying `is_goal`, as its total weight and the nodes along it, with
+    // `start` included.  Found by Dijkstra's algorithm, so none of the weights can be negative.
+    pub fn shortest_path<Q>(
+        &self,
+        start: &Q,
+        mut is_goal: impl FnMut(&N) -> bool,
+    ) -> Option<(W, Vec<&N>)>
+    where
+        N: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        W: Copy + Ord + Default + Add<Output = W>,
+    {
+        let (start, _) = self.edges.get_key_value(start)?;
+
+        // The nodes found so far are numbered in the order they were found, so that the queue doesn't need to
+        // compare them
+        let mut nodes = vec![start];
+        let mut index = HashMap::new();
+        index.insert(start, 0);
+        let mut weights = vec![W::default()];
+        let mut previous: Vec<Option<usize>> = vec![None];
+        let mut visited = vec![false];
+
+        let mut queue = BinaryHeap::new();
+        queue.push(Reverse((W::default(), 0)));
+        while let Some(Reverse((weight, u))) = queue.pop() {
+            if visited[u] {
+                continue;
+            }
+            visited[u] = true;
+
+            if is_goal(nodes[u]) {
+                let mut path = vec![nodes[u]];
+                let mut current = u;
+                while let Some(before) = previous[current] {
+                    path.push(nodes[before]);
+                    current = before;
+                }
+                path.reverse();
+
+                return Some((weight, path));
+            }
+
+            for (v, &edge_weight) in self.neighbours::<N>(nodes[u]) {
+                let through = weight + edge_weight;
+                match index.get(v) {
+                    Some(&v) if visited[v] || weights[v] <= through => {}
+                    Some(&v) => {
+                        weights[v] = through;
+                        previous[v] = Some(u);
+                        queue.push(Reverse((through, v)));
+                    }
+                    None => {
+                        index.insert(v, nodes.len());
+                        queue.push(Reverse((through, nodes.len())));
+                        nodes.push(v);
+                        weights.push(through);
+                        previous.push(Some(u));
+                        visited.push(false);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    fn fold_from<'a, T, F>(
+        &'a self,
+        u: &'a N,
+        f: &mut F,
+        values: &mut HashMap<&'a N, T>,
+        in_progress: &mut HashSet<&'a N>,
+    ) -> Option<T>
+    where
+        T: Clone,
+        F: FnMut(&N, &[(&W, T)]) -> T,
+    {
+        if let Some(value) = values.get(u) {
+            return Some(value.clone());
+        }
+        if !in_progress.insert(u) {
+            return None;
+        }
+
+        let mut children = Vec::new();
+        for (v, weight) in self.neighbours(u) {
+            children.push((weight, self.fold_from(v, f, values, in_progress)?));
+        }
+
+        let value = f(u, &children);
+        in_progress.remove(u);
+        values.insert(u, value.clone());
+
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a -> b -> d, a -> c -> d, with a weight of 2 on every edge out of a
+    fn diamond() -> Graph<&'static str, u32> {
+        let mut graph = Graph::new();
+        graph.add_edge("a", "b", 2);
+        graph.add_edge("a", "c", 2);
+        graph.add_edge("b", "d", 1);
+        graph.add_edge("c", "d", 1);
+        graph.add_node("e");
+        graph
+    }
+
+    #[test]
+    fn test_reachable() {
+        let graph = diamond();
+        assert_eq!(graph.len(), 5);
+
+        let mut reachable: Vec<_> = graph.reachable("a").into_iter().copied().collect();
+        reachable.sort_unstable();
+        assert_eq!(reachable, vec!["b", "c", "d"]);
+
+        assert_eq!(graph.reverse_edges().reachable("d").len(), 3);
+        assert!(graph.reachable("e").is_empty());
+        assert!(graph.reachable("z").is_empty());
+    }
+
+    #[test]
+    fn test_topological_sort() {
+        let graph = diamond();
+        let sorted = graph.topological_sort().unwrap();
+        let position = |u| sorted.iter().position(|v| **v == u).unwrap();
+
+        assert_eq!(sorted.len(), 5);
+        for u in graph.nodes() {
+            for (v, _) in graph.neighbours(u) {
+                assert!(position(*u) < position(*v));
+            }
+        }
+
+        let mut cyclic = diamond();
+        cyclic.add_edge("d", "a", 1);
+        assert_eq!(cyclic.topological_sort(), None);
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        assert_eq!(diamond().find_cycle(), None);
+
+        let mut cyclic = diamond();
+        cyclic.add_edge("d", "b", 1);
+        let mut cycle: Vec<_> = cyclic.find_cycle().unwrap().into_iter().copied().collect();
+        cycle.sort_unstable();
+        assert_eq!(cycle, vec!["b", "d"]);
+    }
+
+    #[test]
+    fn test_fold() {
+        let mut calls = 0;
+        let paths = diamond().fold("a", |_, children| {
+            calls += 1;
+            if children.is_empty() {
+                1
+            } else {
+                children.iter().map(|(weight, paths)| *weight * paths).sum()
+            }
+        });
+
+        assert_eq!(paths, Some(4));
+        assert_eq!(calls, 4);
+
+        let mut cyclic = diamond();
+        cyclic.add_edge("d", "c", 1);
+        assert_eq!(cyclic.fold("a", |_, _| 0), None);
+        assert_eq!(cyclic.fold("z", |_, _| 0), None);
+    }
+
+    #[test]
+    fn test_shortest_path() {
+        let mut graph = diamond();
+        graph.add_edge("b", "e", 4);
+        graph.add_edge("c", "e", 3);
+
+        assert_eq!(
+            graph.shortest_path("a", |u| *u == "d"),
+            Some((3, vec![&"a", &"b", &"d"]))
+        );
+        assert_eq!(
+            graph.shortest_path("a", |u| *u == "e"),
+            Some((5, vec![&"a", &"c", &"e"]))
+        );
+        assert_eq!(
+            graph.shortest_path("a", |u| *u == "a"),
+            Some((0, vec![&"a"]))
+        );
+        assert_eq!(graph.shortest_path("d", |u| *u == "a"), None);
+        assert_eq!(graph.shortest_path("z", |_| true), None);
+    }
+}
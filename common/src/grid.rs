@@ -0,0 +1,343 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+use crate::{error::parse_lines, ParseError};
+
+// A position in a grid, as (x, y) with y increasing down the rows
+pub type Point = (usize, usize);
+
+// Offsets to the orthogonal neighbours of a point, and to all eight including the diagonals
+pub static DIRECTIONS_4: [(i64, i64); 4] = [(1, 0), (0, -1), (-1, 0), (0, 1)];
+pub static DIRECTIONS_8: [(i64, i64); 8] = [
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+// A type which can be read from, and written as, a single character of a map
+pub trait Cell: Sized {
+    // The characters that are accepted, as described in parse errors
+    const EXPECTED: &'static str;
+
+    // What a cell is called in parse errors, e.g. "a row of 3 tiles"
+    const NAME: &'static str = "cell";
+
+    fn from_char(c: char) -> Option<Self>;
+
+    fn to_char(&self) -> char;
+}
+
+// A rectangular grid, which always has at least one row and one column
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    // Returns None unless the rows are all the same, non-zero, length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Grid<T>> {
+        let width = rows
+            .first()
+            .map(|row| row.len())
+            .filter(|&width| width > 0)?;
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+
+        Some(Grid {
+            width,
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Point) -> T) -> Grid<T> {
+        assert!(width > 0 && height > 0, "a grid can't be empty");
+
+        let mut cells = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                cells.push(f((x, y)));
+            }
+        }
+
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): Point) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        if self.contains(p) {
+            Some(&self.cells[p.1 * self.width + p.0])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        if self.contains(p) {
+            Some(&mut self.cells[p.1 * self.width + p.0])
+        } else {
+            None
+        }
+    }
+
+    // Treats the grid as repeating forever in every direction
+    pub fn get_wrapping(&self, x: i64, y: i64) -> &T {
+        let x = x.rem_euclid(self.width as i64) as usize;
+        let y = y.rem_euclid(self.height as i64) as usize;
+
+        &self.cells[y * self.width + x]
+    }
+
+    // The point one step in the given direction from `p`, if it is still in the grid
+    pub fn offset(&self, (x, y): Point, (dx, dy): (i64, i64)) -> Option<Point> {
+        let x = x as i64 + dx;
+        let y = y as i64 + dy;
+        if x < 0 || y < 0 {
+            return None;
+        }
+
+        Some((x as usize, y as usize)).filter(|&p| self.contains(p))
+    }
+
+    pub fn neighbours_4(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        DIRECTIONS_4
+            .iter()
+            .filter_map(move |&direction| self.offset(p, direction))
+    }
+
+    pub fn neighbours_8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        DIRECTIONS_8
+            .iter()
+            .filter_map(move |&direction| self.offset(p, direction))
+    }
+
+    // Every point passed through when moving from `p` in a direction, not including `p` itself, until leaving the
+    // grid
+    pub fn ray(&self, p: Point, direction: (i64, i64)) -> impl Iterator<Item = Point> + '_ {
+        std::iter::successors(self.offset(p, direction), move |&q| {
+            self.offset(q, direction)
+        })
+    }
+
+    // Every point, a row at a time
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        let height = if x < self.width { self.height } else { 0 };
+        self.cells.iter().skip(x).step_by(self.width).take(height)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    pub fn map<U>(&self, mut f: impl FnMut(Point, &T) -> U) -> Grid<U> {
+        Grid::from_fn(self.width, self.height, |p| f(p, &self[p]))
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        self.get(p).unwrap_or_else(|| {
+            panic!(
+                "{:?} is outside of a {}x{} grid",
+                p, self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(p)
+            .unwrap_or_else(|| panic!("{:?} is outside of a {}x{} grid", p, width, height))
+    }
+}
+
+impl<T: Cell> FromStr for Grid<T> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows: Vec<Vec<T>> = parse_lines(s, |line| {
+            line.trim_end()
+                .char_indices()
+                .map(|(idx, c)| {
+                    T::from_char(c).ok_or_else(|| {
+                        ParseError::new(line, &line[idx..idx + c.len_utf8()], T::EXPECTED)
+                    })
+                })
+                .collect()
+        })?;
+
+        let width = match rows.first() {
+            Some(row) if !row.is_empty() => row.len(),
+            _ => {
+                return Err(ParseError::end_of_input(
+                    s,
+                    format!("a row of {}s", T::NAME),
+                ))
+            }
+        };
+
+        if let Some((line, _)) = s
+            .lines()
+            .zip(rows.iter())
+            .find(|(_, row)| row.len() != width)
+        {
+            return Err(ParseError::new(
+                s,
+                line,
+                format!("a row of {} {}s", width, T::NAME),
+            ));
+        }
+
+        Ok(Grid::from_rows(rows).unwrap())
+    }
+}
+
+// Written a row per line, the same as it would be parsed
+impl<T: Cell> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell.to_char())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Tile {
+        Open,
+        Wall,
+    }
+
+    impl Cell for Tile {
+        const EXPECTED: &'static str = "'.' or '#'";
+        const NAME: &'static str = "tile";
+
+        fn from_char(c: char) -> Option<Tile> {
+            match c {
+                '.' => Some(Tile::Open),
+                '#' => Some(Tile::Wall),
+                _ => None,
+            }
+        }
+
+        fn to_char(&self) -> char {
+            match self {
+                Tile::Open => '.',
+                Tile::Wall => '#',
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_display() {
+        let grid: Grid<Tile> = "..#\n#..".parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 0)], Tile::Wall);
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.to_string(), "..#\n#..");
+
+        let err = "..#\n.x.".parse::<Grid<Tile>>().unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        assert_eq!(err.expected, "'.' or '#'");
+
+        let err = "..#\n..".parse::<Grid<Tile>>().unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.expected, "a row of 3 tiles");
+
+        let err = "".parse::<Grid<Tile>>().unwrap_err();
+        assert_eq!(err.expected, "a row of tiles");
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::from_fn(3, 3, |_| ());
+
+        let mut corner: Vec<_> = grid.neighbours_8((0, 0)).collect();
+        corner.sort_unstable();
+        assert_eq!(corner, vec![(0, 1), (1, 0), (1, 1)]);
+
+        assert_eq!(grid.neighbours_4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbours_8((1, 1)).count(), 8);
+    }
+
+    #[test]
+    fn test_ray() {
+        let grid = Grid::from_fn(4, 3, |_| ());
+
+        assert_eq!(
+            grid.ray((0, 0), (1, 1)).collect::<Vec<_>>(),
+            vec![(1, 1), (2, 2)]
+        );
+        assert_eq!(grid.ray((3, 1), (1, 0)).count(), 0);
+    }
+
+    #[test]
+    fn test_rows_columns() {
+        let grid = Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            vec![&[1, 2, 3], &[4, 5, 6]]
+        );
+        assert_eq!(grid.column(1).collect::<Vec<_>>(), vec![&2, &5]);
+        assert_eq!(grid.columns().count(), 3);
+        assert_eq!(grid.column(3).count(), 0);
+        assert_eq!(*grid.get_wrapping(-1, 3), 6);
+
+        assert_eq!(Grid::from_rows(vec![vec![1, 2], vec![3]]), None);
+        assert_eq!(Grid::<u8>::from_rows(vec![]), None);
+    }
+}
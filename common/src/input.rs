@@ -0,0 +1,261 @@
+use std::{
+    env, error, fmt, fs,
+    io::{self, BufRead, BufReader, IsTerminal, Read},
+    path::{Path, PathBuf},
+};
+
+// Where to find a day's puzzle input.  Sources are tried in order: an explicit path (where "-" means stdin), piped
+// stdin, the path in the day's environment variable, the day's input file in the workspace, and finally an embedded
+// copy of the input compiled into the binary.
+pub struct InputSource {
+    day: u8,
+    path: Option<PathBuf>,
+    piped_stdin: bool,
+    embedded: Option<&'static str>,
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    Unreadable(String, io::Error),
+    NotFound(u8),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Unreadable(source, err) => write!(f, "unable to read {}: {}", source, err),
+            InputError::NotFound(day) => write!(
+                f,
+                "no input found for day {}: pass a path, pipe it to stdin, set {} or create {}",
+                day,
+                env_var(*day),
+                default_path(*day).display()
+            ),
+        }
+    }
+}
+
+impl error::Error for InputError {}
+
+// The environment variable which may hold the path to a day's input
+pub fn env_var(day: u8) -> String {
+    format!("AOC_DAY{}_INPUT", day)
+}
+
+// The day's input file, alongside its crate in the workspace
+pub fn default_path(day: u8) -> PathBuf {
+    let mut path_buf = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path_buf.pop();
+    path_buf.push(format!("day{}", day));
+    path_buf.push("input");
+    path_buf
+}
+
+// Converts CRLF line endings to LF, so that parsers only need to split on "\n" and "\n\n"
+pub fn normalise(input: String) -> String {
+    if input.contains('\r') {
+        input.replace("\r\n", "\n")
+    } else {
+        input
+    }
+}
+
+fn read_path(path: &Path) -> Result<String, InputError> {
+    fs::read_to_string(path).map_err(|err| InputError::Unreadable(path.display().to_string(), err))
+}
+
+fn read_stdin() -> Result<String, InputError> {
+    let mut buffer = String::new();
+    io::stdin()
+        .read_to_string(&mut buffer)
+        .map_err(|err| InputError::Unreadable("stdin".to_string(), err))?;
+
+    Ok(buffer)
+}
+
+fn open_path(path: &Path) -> Result<Box<dyn BufRead>, InputError> {
+    match fs::File::open(path) {
+        Ok(file) => Ok(Box::new(BufReader::new(file))),
+        Err(err) => Err(InputError::Unreadable(path.display().to_string(), err)),
+    }
+}
+
+impl InputSource {
+    pub fn new(day: u8) -> InputSource {
+        InputSource {
+            day,
+            path: None,
+            piped_stdin: false,
+            embedded: None,
+        }
+    }
+
+    pub fn path(mut self, path: Option<PathBuf>) -> InputSource {
+        self.path = path;
+        self
+    }
+
+    pub fn piped_stdin(mut self) -> InputSource {
+        self.piped_stdin = true;
+        self
+    }
+
+    pub fn embedded(mut self, input: &'static str) -> InputSource {
+        self.embedded = Some(input);
+        self
+    }
+
+    pub fn read(&self) -> Result<String, InputError> {
+        self.read_raw().map(normalise)
+    }
+
+    // Opens the same source as `read` would, for reading a line at a time.  Line endings are left as they are.
+    pub fn open(&self) -> Result<Box<dyn BufRead>, InputError> {
+        match &self.path {
+            Some(path) if path.as_os_str() == "-" => return Ok(Box::new(io::stdin().lock())),
+            Some(path) => return open_path(path),
+            None => {}
+        }
+
+        if self.piped_stdin && !io::stdin().is_terminal() {
+            let mut stdin = io::stdin().lock();
+            let is_empty = stdin
+                .fill_buf()
+                .map_err(|err| InputError::Unreadable("stdin".to_string(), err))?
+                .is_empty();
+            if !is_empty {
+                return Ok(Box::new(stdin));
+            }
+        }
+
+        if let Some(path) = env::var_os(env_var(self.day)) {
+            return open_path(Path::new(&path));
+        }
+
+        let path = default_path(self.day);
+        match fs::File::open(&path) {
+            Ok(file) => return Ok(Box::new(BufReader::new(file))),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(InputError::Unreadable(path.display().to_string(), err)),
+        }
+
+        match self.embedded {
+            Some(input) => Ok(Box::new(input.as_bytes())),
+            None => Err(InputError::NotFound(self.day)),
+        }
+    }
+
+    fn read_raw(&self) -> Result<String, InputError> {
+        // An explicitly requested source must be readable, rather than silently falling back to another
+        match &self.path {
+            Some(path) if path.as_os_str() == "-" => return read_stdin(),
+            Some(path) => return read_path(path),
+            None => {}
+        }
+
+        // Nothing at all on stdin (e.g. when it is /dev/null) is treated as nothing having been piped
+        if self.piped_stdin && !io::stdin().is_terminal() {
+            let input = read_stdin()?;
+            if !input.is_empty() {
+                return Ok(input);
+            }
+        }
+
+        if let Some(path) = env::var_os(env_var(self.day)) {
+            return read_path(Path::new(&path));
+        }
+
+        let path = default_path(self.day);
+        match fs::read_to_string(&path) {
+            Ok(input) => return Ok(input),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(InputError::Unreadable(path.display().to_string(), err)),
+        }
+
+        match self.embedded {
+            Some(input) => Ok(input.to_string()),
+            None => Err(InputError::NotFound(self.day)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(name: &str, contents: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("aoc-input-{}-{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_normalise() {
+        assert_eq!(normalise("a\r\nb\r\n\r\nc".to_string()), "a\nb\n\nc");
+        assert_eq!(normalise("a\nb".to_string()), "a\nb");
+    }
+
+    #[test]
+    fn test_explicit_path() {
+        let path = temp_file("explicit", "1\r\n2\r\n");
+        let input = InputSource::new(1)
+            .path(Some(path.clone()))
+            .embedded("3\n")
+            .read();
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(input.unwrap(), "1\n2\n");
+    }
+
+    #[test]
+    fn test_missing_explicit_path() {
+        let input = InputSource::new(1)
+            .path(Some(PathBuf::from("/nonexistent/input")))
+            .embedded("3\n")
+            .read();
+
+        assert!(
+            matches!(input, Err(InputError::Unreadable(source, _)) if source == "/nonexistent/input")
+        );
+    }
+
+    #[test]
+    fn test_env_var() {
+        // Day numbers beyond 25 are used so as not to interfere with any real inputs
+        let path = temp_file("env", "4\n");
+        env::set_var(env_var(101), &path);
+        let input = InputSource::new(101).embedded("3\n").read();
+        env::remove_var(env_var(101));
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(input.unwrap(), "4\n");
+    }
+
+    #[test]
+    fn test_open() {
+        let path = temp_file("open", "6\r\n7\n");
+        let reader = InputSource::new(1).path(Some(path.clone())).open();
+        let lines: Vec<String> = reader.unwrap().lines().map(Result::unwrap).collect();
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(lines, vec!["6", "7"]);
+
+        let reader = InputSource::new(104).embedded("8\n").open();
+        assert_eq!(reader.unwrap().lines().count(), 1);
+    }
+
+    #[test]
+    fn test_embedded() {
+        let input = InputSource::new(102).embedded("5\r\n").read();
+
+        assert_eq!(input.unwrap(), "5\n");
+    }
+
+    #[test]
+    fn test_not_found() {
+        let err = InputSource::new(103).read().unwrap_err();
+
+        assert!(matches!(err, InputError::NotFound(103)));
+        assert!(err.to_string().starts_with("no input found for day 103: "));
+    }
+}
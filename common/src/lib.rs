@@ -1,7 +1,22 @@
-#[cfg(test)]
-mod tests {
-    #[test]
-    fn it_works() {
-        assert_eq!(2 + 2, 4);
-    }
-}
+pub mod answer;
+pub mod automaton;
+pub mod bench;
+pub mod error;
+pub mod generate;
+pub mod graph;
+pub mod grid;
+pub mod input;
+pub mod manifest;
+pub mod numtheory;
+pub mod parser;
+pub mod pool;
+pub mod runner;
+pub mod solution;
+pub mod stream;
+pub mod trace;
+pub mod visualize;
+
+pub use answer::Answer;
+pub use error::ParseError;
+pub use grid::Grid;
+pub use solution::Solution;
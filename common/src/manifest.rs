@@ -0,0 +1,205 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    input::normalise,
+    runner::{Day, Parts},
+};
+
+// An input whose answers are known, so that any change to them can be caught.  The input's path is relative to the
+// manifest, and the hash guards against the input itself changing underneath its answers.  A part without an answer
+// isn't run, as some examples only make sense for one part.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub day: u8,
+    pub input: PathBuf,
+    pub sha256: String,
+    #[serde(default)]
+    pub part_1: Option<String>,
+    #[serde(default)]
+    pub part_2: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch {
+    Unreadable(String),
+    InputChanged {
+        expected: String,
+        found: String,
+    },
+    Failed(String),
+    Answer {
+        part: u8,
+        expected: String,
+        found: String,
+    },
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mismatch::Unreadable(reason) | Mismatch::Failed(reason) => write!(f, "{}", reason),
+            Mismatch::InputChanged { expected, found } => write!(
+                f,
+                "input has changed\n  - sha256 {}\n  + sha256 {}",
+                expected, found
+            ),
+            Mismatch::Answer {
+                part,
+                expected,
+                found,
+            } => write!(f, "part {} changed\n  - {}\n  + {}", part, expected, found),
+        }
+    }
+}
+
+// The manifest of the workspace's inputs, alongside the example inputs it lists
+pub fn default_path() -> PathBuf {
+    let mut path_buf = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path_buf.pop();
+    path_buf.push("answers");
+    path_buf.push("manifest.json");
+    path_buf
+}
+
+pub fn load(path: &Path) -> Result<Vec<Entry>, String> {
+    let json = fs::read_to_string(path)
+        .map_err(|err| format!("unable to read {}: {}", path.display(), err))?;
+
+    serde_json::from_str(&json)
+        .map_err(|err| format!("unable to parse {}: {}", path.display(), err))
+}
+
+pub fn sha256(input: &str) -> String {
+    format!("{:x}", Sha256::digest(input.as_bytes()))
+}
+
+// Solves an entry's input, returning every way in which the result differs from the manifest.  `base` is the
+// directory containing the manifest.
+pub fn check(entry: &Entry, base: &Path, day: &Day) -> Vec<Mismatch> {
+    let path = base.join(&entry.input);
+    let input = match fs::read_to_string(&path) {
+        Ok(input) => normalise(input),
+        Err(err) => {
+            return vec![Mismatch::Unreadable(format!(
+                "unable to read {}: {}",
+                path.display(),
+                err
+            ))]
+        }
+    };
+
+    // The answers can't be expected to match if the input they were found from has changed
+    let found = sha256(&input);
+    if found != entry.sha256 {
+        return vec![Mismatch::InputChanged {
+            expected: entry.sha256.clone(),
+            found,
+        }];
+    }
+
+    let parts = match (&entry.part_1, &entry.part_2) {
+        (Some(_), Some(_)) => Parts::Both,
+        (Some(_), None) => Parts::One,
+        (None, Some(_)) => Parts::Two,
+        (None, None) => return vec![],
+    };
+
+    let solved = match day.run(&input, parts).outcome {
+        Ok(solved) => solved,
+        Err(reason) => return vec![Mismatch::Failed(reason)],
+    };
+
+    let expected = [&entry.part_1, &entry.part_2];
+    let found = [&solved.part_1, &solved.part_2];
+    (1..)
+        .zip(expected.iter().zip(found.iter()))
+        .filter_map(|(part, (expected, found))| match (expected, found) {
+            (Some(expected), Some(found)) if *expected != found.answer.to_string() => {
+                Some(Mismatch::Answer {
+                    part,
+                    expected: expected.clone(),
+                    found: found.answer.to_string(),
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::{error::parse_number, Answer, ParseError, Solution};
+
+    struct Halver;
+
+    impl Solution for Halver {
+        type Input = i64;
+
+        const DAY: u8 = 1;
+
+        fn parse(input: &str) -> Result<i64, ParseError> {
+            parse_number(input, input.trim())
+        }
+
+        fn part1(input: &i64) -> Answer {
+            (input / 2).into()
+        }
+
+        fn part2(input: &i64) -> Answer {
+            (input % 2).into()
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        let base = env::temp_dir();
+        let name = format!("aoc-manifest-{}", std::process::id());
+        fs::write(base.join(&name), "21\r\n").unwrap();
+
+        let entry = Entry {
+            day: 1,
+            input: PathBuf::from(&name),
+            sha256: sha256("21\n"),
+            part_1: Some("10".to_string()),
+            part_2: Some("0".to_string()),
+        };
+        let day = Day::of::<Halver>();
+
+        let mismatches = check(&entry, &base, &day);
+        assert_eq!(
+            mismatches,
+            vec![Mismatch::Answer {
+                part: 2,
+                expected: "0".to_string(),
+                found: "1".to_string()
+            }]
+        );
+        assert_eq!(mismatches[0].to_string(), "part 2 changed\n  - 0\n  + 1");
+
+        let only_part_1 = Entry {
+            part_2: None,
+            ..entry.clone()
+        };
+        assert_eq!(check(&only_part_1, &base, &day), vec![]);
+
+        let changed_input = Entry {
+            sha256: sha256("22\n"),
+            ..entry
+        };
+        assert!(matches!(
+            check(&changed_input, &base, &day)[..],
+            [Mismatch::InputChanged { .. }]
+        ));
+
+        fs::remove_file(base.join(&name)).unwrap();
+    }
+}
@@ -0,0 +1,300 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+// Returns the greatest common divisor `d` of `x` and `y`, along with `a` and `b` such that `a * x + b * y == d`
+pub fn extended_euclid(x: i128, y: i128) -> (i128, (i128, i128)) {
+    let (mut d, mut d_prev) = (x, y);
+    let (mut a, mut a_prev) = (1, 0);
+    let (mut b, mut b_prev) = (0, 1);
+
+    while d_prev != 0 {
+        let q = d / d_prev;
+
+        (d, d_prev) = (d_prev, d - q * d_prev);
+        (a, a_prev) = (a_prev, a - q * a_prev);
+        (b, b_prev) = (b_prev, b - q * b_prev);
+    }
+
+    if d < 0 {
+        (-d, (-a, -b))
+    } else {
+        (d, (a, b))
+    }
+}
+
+pub fn gcd(x: i128, y: i128) -> i128 {
+    extended_euclid(x, y).0
+}
+
+pub fn lcm(x: i128, y: i128) -> i128 {
+    x / gcd(x, y) * y
+}
+
+pub fn mod_pow(base: i128, mut exponent: u64, modulus: i128) -> i128 {
+    let mut base = base.rem_euclid(modulus);
+    let mut result = 1 % modulus;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exponent >>= 1;
+    }
+
+    result
+}
+
+// The inverse of `a` modulo `modulus`, which only exists if they are coprime
+pub fn mod_inv(a: i128, modulus: i128) -> Option<i128> {
+    match extended_euclid(a.rem_euclid(modulus), modulus) {
+        (1, (inverse, _)) => Some(inverse.rem_euclid(modulus)),
+        _ => None,
+    }
+}
+
+// Solves a system of linear congruences in `x`, given as pairs of (`a_i`, `n_i`) such that:
+// `x` === `a_1` (mod `n_1`)
+// `x` === `a_2` (mod `n_2`)
+// ...
+// `x` === `a_k` (mod `n_k`)
+//
+// The moduli needn't be coprime.  Returns the solution `a` (if one exists) along with the modulus `n`, the lowest
+// common multiple of the `n_i`, such that every solution is `a` modulo `n`, with 0 <= `a` < `n`.
+pub fn chinese_remainder(linear_congruences: &[(i128, i128)]) -> Option<(i128, i128)> {
+    let mut linear_congruences = linear_congruences.iter();
+    let (a_0, n_0) = linear_congruences.next()?;
+    let mut a = a_0.rem_euclid(*n_0);
+    let mut n = *n_0;
+
+    for (a_i, n_i) in linear_congruences {
+        // x = a + n * k, so n * k === a_i - a (mod n_i), which only has a solution if the gcd divides the difference
+        let (d, (u, _)) = extended_euclid(n, *n_i);
+        let difference = a_i - a;
+        if difference % d != 0 {
+            return None;
+        }
+
+        let step = n_i / d;
+        let k = (difference / d % step * u).rem_euclid(step);
+        a += n * k;
+        n *= step;
+        a = a.rem_euclid(n);
+    }
+
+    Some((a, n))
+}
+
+// Finds the least `e` with `base` ^ `e` === `target` (mod `modulus`), where `base` and `modulus` are coprime, by
+// baby-step giant-step
+pub fn discrete_log(base: i128, target: i128, modulus: i128) -> Option<u64> {
+    let target = target.rem_euclid(modulus);
+    let m = (modulus as f64).sqrt().ceil() as u64;
+
+    // The baby steps, base ^ j for 0 <= j < m, keeping the lowest j for each value
+    let mut baby_steps = HashMap::with_capacity(m as usize);
+    let mut value = 1 % modulus;
+    for j in 0..m {
+        baby_steps.entry(value).or_insert(j);
+        value = value * base % modulus;
+    }
+
+    // Each giant step divides the target by base ^ m
+    let giant_step = mod_inv(mod_pow(base, m, modulus), modulus)?;
+    let mut value = target;
+    for i in 0..m {
+        if let Some(j) = baby_steps.get(&value) {
+            return Some(i * m + j);
+        }
+        value = value * giant_step % modulus;
+    }
+
+    None
+}
+
+// An integer modulo `M`, which is always kept between 0 and `M`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ModInt<const M: u64>(u64);
+
+impl<const M: u64> ModInt<M> {
+    pub fn new(n: i128) -> ModInt<M> {
+        ModInt(n.rem_euclid(M as i128) as u64)
+    }
+
+    pub fn value(self) -> u64 {
+        self.0
+    }
+
+    pub fn pow(self, exponent: u64) -> ModInt<M> {
+        ModInt(mod_pow(self.0 as i128, exponent, M as i128) as u64)
+    }
+
+    pub fn inv(self) -> Option<ModInt<M>> {
+        mod_inv(self.0 as i128, M as i128).map(|inverse| ModInt(inverse as u64))
+    }
+
+    // The least `e` with self ^ `e` == `target`
+    pub fn log(self, target: ModInt<M>) -> Option<u64> {
+        discrete_log(self.0 as i128, target.0 as i128, M as i128)
+    }
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(n: u64) -> ModInt<M> {
+        ModInt(n % M)
+    }
+}
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = ModInt<M>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        ModInt(((self.0 as u128 + rhs.0 as u128) % M as u128) as u64)
+    }
+}
+
+impl<const M: u64> AddAssign for ModInt<M> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const M: u64> Neg for ModInt<M> {
+    type Output = ModInt<M>;
+
+    fn neg(self) -> Self::Output {
+        ModInt((M - self.0) % M)
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = ModInt<M>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl<const M: u64> SubAssign for ModInt<M> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = ModInt<M>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        ModInt((self.0 as u128 * rhs.0 as u128 % M as u128) as u64)
+    }
+}
+
+impl<const M: u64> MulAssign for ModInt<M> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<const M: u64> fmt::Display for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_euclid() {
+        let (d, (a, b)) = extended_euclid(19, 25);
+        assert_eq!(a * 19 + b * 25, d);
+        assert_eq!(d, 1);
+
+        assert_eq!(gcd(12, -18), 6);
+        assert_eq!(lcm(4, 6), 12);
+    }
+
+    #[test]
+    fn test_mod_pow_inv() {
+        assert_eq!(mod_pow(7, 8, 20201227), 5764801);
+        assert_eq!(mod_pow(-2, 3, 5), 2);
+        assert_eq!(mod_pow(3, 0, 1), 0);
+
+        assert_eq!(mod_inv(3, 7), Some(5));
+        assert_eq!(mod_inv(-3, 7), Some(2));
+        assert_eq!(mod_inv(4, 6), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        let (x, n) = chinese_remainder(&[(0, 3), (3, 4), (4, 5)]).unwrap();
+
+        assert_eq!(0, x % 3);
+        assert_eq!(3, x % 4);
+        assert_eq!(4, x % 5);
+        assert_eq!(n, 60);
+
+        assert_eq!(x % 60, x);
+    }
+
+    #[test]
+    fn test_crt_not_coprime() {
+        assert_eq!(chinese_remainder(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(chinese_remainder(&[(1, 4), (2, 6)]), None);
+        assert_eq!(chinese_remainder(&[(-1, 5)]), Some((4, 5)));
+        assert_eq!(chinese_remainder(&[]), None);
+    }
+
+    #[test]
+    fn test_discrete_log() {
+        assert_eq!(discrete_log(7, 5764801, 20201227), Some(8));
+        assert_eq!(discrete_log(7, 17807724, 20201227), Some(11));
+        assert_eq!(discrete_log(2, 1, 7), Some(0));
+        assert_eq!(discrete_log(2, 3, 7), None);
+    }
+
+    #[test]
+    fn test_mod_int() {
+        type Z7 = ModInt<7>;
+
+        let x = Z7::new(-1);
+        assert_eq!(x.value(), 6);
+        assert_eq!(x + Z7::from(3), Z7::from(2));
+        assert_eq!(Z7::from(2) - Z7::from(5), Z7::from(4));
+        assert_eq!(x * x, Z7::from(1));
+        assert_eq!(Z7::from(3).inv(), Some(Z7::from(5)));
+        assert_eq!(Z7::from(0).inv(), None);
+        assert_eq!(Z7::from(3).pow(6), Z7::from(1));
+        assert_eq!(Z7::from(3).log(Z7::from(6)), Some(3));
+        assert_eq!(Z7::from(10).to_string(), "3");
+    }
+
+    proptest! {
+        // A system has a solution exactly when every pair of congruences agree modulo the gcd of their moduli
+        #[test]
+        fn prop_crt(congruences in prop::collection::vec((-1000..1000i128, 1..1000i128), 1..6)) {
+            let compatible = congruences.iter().enumerate().all(|(i, (a_i, n_i))| {
+                congruences[i + 1..]
+                    .iter()
+                    .all(|(a_j, n_j)| (a_i - a_j) % gcd(*n_i, *n_j) == 0)
+            });
+
+            match chinese_remainder(&congruences) {
+                Some((x, n)) => {
+                    prop_assert!(compatible);
+                    prop_assert_eq!(n, congruences.iter().fold(1, |n, (_, n_i)| lcm(n, *n_i)));
+                    prop_assert!((0..n).contains(&x));
+                    for (a_i, n_i) in congruences.iter() {
+                        prop_assert_eq!((x - a_i).rem_euclid(*n_i), 0);
+                    }
+                }
+                None => prop_assert!(!compatible),
+            }
+        }
+    }
+}
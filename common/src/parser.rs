@@ -0,0 +1,362 @@
+use std::str::FromStr;
+
+use crate::ParseError;
+
+// Parser combinators for declaring an input's grammar.  A parser takes the remaining input and returns what it
+// parsed along with the input left over, or a failure holding a slice of the input, so that the failure can be
+// turned into a `ParseError` with its line and column once parsing is finished.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure<'a> {
+    pub snippet: &'a str,
+    pub expected: String,
+}
+
+impl<'a> Failure<'a> {
+    pub fn new(snippet: &'a str, expected: impl Into<String>) -> Failure<'a> {
+        Failure {
+            snippet,
+            expected: expected.into(),
+        }
+    }
+
+    // A failure at the start of `rest`, showing the token found there
+    pub fn at(rest: &'a str, expected: impl Into<String>) -> Failure<'a> {
+        Failure::new(next_token(rest), expected)
+    }
+
+    fn position(&self) -> usize {
+        self.snippet.as_ptr() as usize
+    }
+}
+
+pub type PResult<'a, T> = Result<(T, &'a str), Failure<'a>>;
+
+pub trait Parser<'a, T> {
+    fn parse(&self, input: &'a str) -> PResult<'a, T>;
+}
+
+impl<'a, T, F> Parser<'a, T> for F
+where
+    F: Fn(&'a str) -> PResult<'a, T>,
+{
+    fn parse(&self, input: &'a str) -> PResult<'a, T> {
+        self(input)
+    }
+}
+
+// A string parses itself
+impl<'a> Parser<'a, &'a str> for &'static str {
+    fn parse(&self, input: &'a str) -> PResult<'a, &'a str> {
+        literal(self).parse(input)
+    }
+}
+
+// A tuple of parsers parses each in turn
+macro_rules! tuple_parser {
+    ($($p:ident $t:ident),+) => {
+        impl<'a, $($p, $t),+> Parser<'a, ($($t,)+)> for ($($p,)+)
+        where
+            $($p: Parser<'a, $t>),+
+        {
+            #[allow(non_snake_case)]
+            fn parse(&self, rest: &'a str) -> PResult<'a, ($($t,)+)> {
+                let ($($p,)+) = self;
+                $(let ($t, rest) = $p.parse(rest)?;)+
+                Ok((($($t,)+), rest))
+            }
+        }
+    };
+}
+
+tuple_parser!(A T1, B T2);
+tuple_parser!(A T1, B T2, C T3);
+tuple_parser!(A T1, B T2, C T3, D T4);
+tuple_parser!(A T1, B T2, C T3, D T4, E T5);
+tuple_parser!(A T1, B T2, C T3, D T4, E T5, F T6);
+tuple_parser!(A T1, B T2, C T3, D T4, E T5, F T6, G T7);
+
+// The token at the start of `rest`, for showing in an error: a word or number, a single other character, or nothing
+// at the end of the input
+fn next_token(rest: &str) -> &str {
+    let end = rest
+        .char_indices()
+        .find(|(_, c)| !c.is_alphanumeric())
+        .map_or(rest.len(), |(idx, _)| idx);
+
+    match rest.chars().next() {
+        Some(c) if end == 0 => &rest[..c.len_utf8()],
+        _ => &rest[..end],
+    }
+}
+
+// Parses the whole of `input`, other than any trailing whitespace
+pub fn parse<'a, T>(input: &'a str, parser: impl Parser<'a, T>) -> Result<T, ParseError> {
+    let failure = match parser.parse(input) {
+        Ok((value, rest)) if rest.trim().is_empty() => return Ok(value),
+        Ok((_, rest)) => Failure::at(rest, "end of input"),
+        Err(failure) => failure,
+    };
+
+    Err(ParseError::new(input, failure.snippet, failure.expected))
+}
+
+pub fn literal<'a>(expected: &'static str) -> impl Parser<'a, &'a str> {
+    move |rest: &'a str| match rest.strip_prefix(expected) {
+        Some(remaining) => Ok((&rest[..expected.len()], remaining)),
+        None => Err(Failure::at(rest, format!("{:?}", expected))),
+    }
+}
+
+// One or more characters matching `predicate`
+pub fn take_while1<'a>(
+    predicate: impl Fn(char) -> bool,
+    expected: &'static str,
+) -> impl Parser<'a, &'a str> {
+    move |rest: &'a str| {
+        let end = rest
+            .char_indices()
+            .find(|&(_, c)| !predicate(c))
+            .map_or(rest.len(), |(idx, _)| idx);
+
+        if end == 0 {
+            Err(Failure::at(rest, expected))
+        } else {
+            Ok(rest.split_at(end))
+        }
+    }
+}
+
+// Everything up to the end of the line, which mustn't be empty
+pub fn rest_of_line<'a>(expected: &'static str) -> impl Parser<'a, &'a str> {
+    take_while1(|c| c != '\n', expected)
+}
+
+pub fn word<'a>() -> impl Parser<'a, &'a str> {
+    take_while1(char::is_alphabetic, "a word")
+}
+
+// An optionally signed integer
+pub fn integer<'a, T: FromStr>() -> impl Parser<'a, T> {
+    move |rest: &'a str| {
+        let sign = if rest.starts_with(&['-', '+'][..]) {
+            1
+        } else {
+            0
+        };
+        let digits = rest[sign..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len() - sign);
+        if digits == 0 {
+            return Err(Failure::at(rest, "a number"));
+        }
+
+        let (number, remaining) = rest.split_at(sign + digits);
+        match number.parse() {
+            Ok(value) => Ok((value, remaining)),
+            Err(_) => Err(Failure::new(number, "a number")),
+        }
+    }
+}
+
+pub fn map<'a, T, U>(parser: impl Parser<'a, T>, f: impl Fn(T) -> U) -> impl Parser<'a, U> {
+    move |rest: &'a str| {
+        let (value, remaining) = parser.parse(rest)?;
+        Ok((f(value), remaining))
+    }
+}
+
+// Converts what was parsed, with a failure showing everything that the parser consumed
+pub fn try_map<'a, T, U>(
+    parser: impl Parser<'a, T>,
+    f: impl Fn(T) -> Result<U, String>,
+) -> impl Parser<'a, U> {
+    move |rest: &'a str| {
+        let (value, remaining) = parser.parse(rest)?;
+        let consumed = &rest[..rest.len() - remaining.len()];
+
+        match f(value) {
+            Ok(value) => Ok((value, remaining)),
+            Err(expected) => Err(Failure::new(consumed, expected)),
+        }
+    }
+}
+
+// Tries `first` and then `second`, failing with whichever got further, or with both of their expectations if they
+// failed at the same point
+pub fn or<'a, T>(first: impl Parser<'a, T>, second: impl Parser<'a, T>) -> impl Parser<'a, T> {
+    move |rest: &'a str| {
+        let first_failure = match first.parse(rest) {
+            Ok(result) => return Ok(result),
+            Err(failure) => failure,
+        };
+        let second_failure = match second.parse(rest) {
+            Ok(result) => return Ok(result),
+            Err(failure) => failure,
+        };
+
+        Err(
+            match first_failure.position().cmp(&second_failure.position()) {
+                std::cmp::Ordering::Greater => first_failure,
+                std::cmp::Ordering::Less => second_failure,
+                std::cmp::Ordering::Equal => Failure {
+                    expected: format!("{} or {}", first_failure.expected, second_failure.expected),
+                    ..first_failure
+                },
+            },
+        )
+    }
+}
+
+pub fn optional<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, Option<T>> {
+    move |rest: &'a str| match parser.parse(rest) {
+        Ok((value, remaining)) => Ok((Some(value), remaining)),
+        Err(_) => Ok((None, rest)),
+    }
+}
+
+// One or more of `parser`, separated by `separator`.  Once a separator has been parsed there must be another item.
+pub fn separated<'a, T, S>(
+    parser: impl Parser<'a, T>,
+    separator: impl Parser<'a, S>,
+) -> impl Parser<'a, Vec<T>> {
+    move |rest: &'a str| {
+        let (first, mut rest) = parser.parse(rest)?;
+        let mut values = vec![first];
+
+        while let Ok((_, after_separator)) = separator.parse(rest) {
+            let (value, remaining) = parser.parse(after_separator)?;
+            values.push(value);
+            rest = remaining;
+        }
+
+        Ok((values, rest))
+    }
+}
+
+// One or more lines, each of which must be entirely parsed by `parser`, up to a blank line or the end of the input
+pub fn lines<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |mut rest: &'a str| {
+        let mut values = Vec::new();
+
+        loop {
+            let (value, remaining) = parser.parse(rest)?;
+            values.push(value);
+
+            rest = match remaining.strip_prefix('\n') {
+                Some(next) if !next.is_empty() && !next.starts_with('\n') => next,
+                _ if remaining.is_empty() || remaining.starts_with('\n') => {
+                    return Ok((values, remaining))
+                }
+                _ => return Err(Failure::at(remaining, "end of line")),
+            };
+        }
+    }
+}
+
+// One or more groups separated by blank lines, each parsed by `parser`
+pub fn groups<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |rest: &'a str| {
+        let (first, mut rest) = parser.parse(rest)?;
+        let mut values = vec![first];
+
+        while let Some(next) = rest.strip_prefix("\n\n") {
+            let next = next.trim_start_matches('\n');
+            if next.is_empty() {
+                break;
+            }
+
+            let (value, remaining) = parser.parse(next)?;
+            values.push(value);
+            rest = remaining;
+        }
+
+        Ok((values, rest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range<'a>() -> impl Parser<'a, (u32, u32)> {
+        map((integer(), "-", integer()), |(a, _, b)| (a, b))
+    }
+
+    #[test]
+    fn test_sequence() {
+        assert_eq!(parse("3-14", range()), Ok((3, 14)));
+        assert_eq!(parse("-7", integer::<i8>()), Ok(-7));
+        assert_eq!(parse("a b", (word(), " ", word())), Ok(("a", " ", "b")));
+
+        let err = parse("3-x4", range()).unwrap_err();
+        assert_eq!((err.column, err.snippet.as_str()), (3, "x4"));
+        assert_eq!(err.expected, "a number");
+
+        let err = parse("3+4", range()).unwrap_err();
+        assert_eq!((err.column, err.snippet.as_str()), (2, "+"));
+        assert_eq!(err.expected, "\"-\"");
+
+        let err = parse("300", integer::<u8>()).unwrap_err();
+        assert_eq!(err.snippet, "300");
+
+        let err = parse("1-2 x", range()).unwrap_err();
+        assert_eq!((err.column, err.expected.as_str()), (4, "end of input"));
+    }
+
+    #[test]
+    fn test_or() {
+        let parser = || {
+            or(
+                map(("on ", integer()), |(_, n)| Some(n)),
+                map(("off ", word()), |_| None::<u32>),
+            )
+        };
+
+        assert_eq!(parse("on 4", parser()), Ok(Some(4)));
+        assert_eq!(parse("off now", parser()), Ok(None));
+
+        let err = parse("o", parser()).unwrap_err();
+        assert_eq!(err.expected, "\"on \" or \"off \"");
+
+        let err = parse("on x", parser()).unwrap_err();
+        assert_eq!((err.column, err.expected.as_str()), (4, "a number"));
+    }
+
+    #[test]
+    fn test_try_map() {
+        let even = try_map(integer::<u32>(), |n| {
+            if n % 2 == 0 {
+                Ok(n)
+            } else {
+                Err("an even number".to_string())
+            }
+        });
+
+        let err = parse("x: 135", ("x: ", even)).unwrap_err();
+        assert_eq!((err.column, err.snippet.as_str()), (4, "135"));
+        assert_eq!(err.expected, "an even number");
+    }
+
+    #[test]
+    fn test_lists() {
+        let tickets = || lines(separated(integer::<u32>(), ","));
+
+        assert_eq!(parse("1,2\n3\n", tickets()), Ok(vec![vec![1, 2], vec![3]]));
+
+        let err = parse("1,2\n3,,4", tickets()).unwrap_err();
+        assert_eq!((err.line, err.column, err.snippet.as_str()), (2, 3, ","));
+
+        let err = parse("1,2\n3 4", tickets()).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        assert_eq!(err.expected, "end of line");
+
+        let input = "1,2\n3\n\n\n4\n\n";
+        assert_eq!(
+            parse(input, groups(tickets())),
+            Ok(vec![vec![vec![1, 2], vec![3]], vec![vec![4]]])
+        );
+        assert_eq!(parse("5", optional(integer::<u32>())), Ok(Some(5)));
+        assert_eq!(parse("", optional(integer::<u32>())), Ok(None));
+    }
+}
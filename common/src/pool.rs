@@ -0,0 +1,210 @@
+use std::{
+    sync::{Condvar, Mutex},
+    thread,
+};
+
+// A fixed number of worker threads sharing a memory budget.  Each task says how much memory it needs, and is only
+// started once that much of the budget is free, so that the few days with large tables don't all allocate them at
+// once.  A task needing more than the whole budget is run on its own, with no other task started until it's finished.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Pool {
+    workers: usize,
+    memory: usize,
+}
+
+struct Schedule<T> {
+    pending: Vec<Option<(usize, T)>>,
+    available: usize,
+    running: usize,
+    // Whether the running task is one which needed more than the whole budget
+    alone: bool,
+}
+
+impl<T> Schedule<T> {
+    // The first pending task which fits in the available memory, or any task at all if nothing else is running
+    fn next(&mut self) -> Option<(usize, usize, T)> {
+        if self.alone {
+            return None;
+        }
+        let idx = self.pending.iter().position(|task| match task {
+            Some((memory, _)) => *memory <= self.available || self.running == 0,
+            None => false,
+        })?;
+
+        let (memory, task) = self.pending[idx].take().unwrap();
+        self.alone = memory > self.available;
+        let reserved = memory.min(self.available);
+        self.available -= reserved;
+        self.running += 1;
+
+        Some((idx, reserved, task))
+    }
+
+    fn is_finished(&self) -> bool {
+        self.pending.iter().all(Option::is_none)
+    }
+}
+
+// Memory reserved by a running task, which is given back when the task finishes, even if it panicked
+struct Reservation<'a, T> {
+    schedule: &'a Mutex<Schedule<T>>,
+    freed: &'a Condvar,
+    memory: usize,
+}
+
+impl<T> Drop for Reservation<'_, T> {
+    fn drop(&mut self) {
+        let mut schedule = self.schedule.lock().unwrap_or_else(|err| err.into_inner());
+        schedule.available += self.memory;
+        schedule.running -= 1;
+        schedule.alone = false;
+        self.freed.notify_all();
+    }
+}
+
+impl Pool {
+    // A pool with no limit on memory
+    pub fn new(workers: usize) -> Pool {
+        Pool {
+            workers: workers.max(1),
+            memory: usize::MAX,
+        }
+    }
+
+    // A pool with one worker for each of the machine's threads
+    pub fn available() -> Pool {
+        Pool::new(thread::available_parallelism().map_or(1, |n| n.get()))
+    }
+
+    pub fn memory_limit(self, bytes: usize) -> Pool {
+        Pool {
+            memory: bytes,
+            ..self
+        }
+    }
+
+    pub fn workers(&self) -> usize {
+        self.workers
+    }
+
+    // Runs `f` on every task, given as the memory it needs along with its argument, returning the results in the
+    // same order as the tasks.  Tasks are started in order, other than that one which doesn't fit yet is passed over
+    // for later ones which do.
+    pub fn run<T, R, F>(&self, tasks: Vec<(usize, T)>, f: F) -> Vec<R>
+    where
+        T: Send,
+        R: Send,
+        F: Fn(T) -> R + Sync,
+    {
+        let count = tasks.len();
+        let schedule = Mutex::new(Schedule {
+            pending: tasks.into_iter().map(Some).collect(),
+            available: self.memory,
+            running: 0,
+            alone: false,
+        });
+        let freed = Condvar::new();
+        let results = Mutex::new((0..count).map(|_| None).collect::<Vec<Option<R>>>());
+
+        thread::scope(|scope| {
+            for _ in 0..self.workers.min(count) {
+                scope.spawn(|| loop {
+                    let (idx, memory, task) = {
+                        let mut schedule = schedule.lock().unwrap();
+                        loop {
+                            if let Some(next) = schedule.next() {
+                                break next;
+                            }
+                            if schedule.is_finished() {
+                                return;
+                            }
+                            schedule = freed.wait(schedule).unwrap();
+                        }
+                    };
+
+                    let _reservation = Reservation {
+                        schedule: &schedule,
+                        freed: &freed,
+                        memory,
+                    };
+                    let result = f(task);
+                    results.lock().unwrap()[idx] = Some(result);
+                });
+            }
+        });
+
+        results
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .map(|result| result.expect("every task is run"))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    #[test]
+    fn test_order() {
+        let tasks = (0..20).map(|n| (0, n)).collect();
+        let squares = Pool::new(4).run(tasks, |n: u64| {
+            thread::sleep(Duration::from_millis(20 - n));
+            n * n
+        });
+
+        assert_eq!(squares, (0..20).map(|n| n * n).collect::<Vec<_>>());
+        assert!(Pool::new(3)
+            .run(Vec::<(usize, ())>::new(), |_| ())
+            .is_empty());
+    }
+
+    #[test]
+    fn test_memory_limit() {
+        let in_use = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let tasks = vec![(60, 60), (50, 50), (30, 30), (100, 100), (10, 10), (40, 40)];
+
+        Pool::new(6).memory_limit(100).run(tasks, |memory| {
+            let now = in_use.fetch_add(memory, Ordering::SeqCst) + memory;
+            peak.fetch_max(now, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(10));
+            in_use.fetch_sub(memory, Ordering::SeqCst);
+        });
+
+        assert!(peak.load(Ordering::SeqCst) <= 100);
+    }
+
+    #[test]
+    fn test_oversized_task() {
+        let running = AtomicUsize::new(0);
+        let tasks = vec![(1, false), (500, true), (1, false)];
+
+        let alone = Pool::new(3).memory_limit(100).run(tasks, |oversized| {
+            running.fetch_add(1, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(10));
+            let alone = running.load(Ordering::SeqCst) == 1;
+            running.fetch_sub(1, Ordering::SeqCst);
+            !oversized || alone
+        });
+
+        assert_eq!(alone, vec![true, true, true]);
+
+        // Tasks which need no memory at all still wait for it to finish
+        let tasks = vec![(500, true), (0, false), (0, false), (0, false)];
+        let alone = Pool::new(4).memory_limit(100).run(tasks, |oversized| {
+            running.fetch_add(1, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(10));
+            let alone = running.load(Ordering::SeqCst) == 1;
+            running.fetch_sub(1, Ordering::SeqCst);
+            !oversized || alone
+        });
+
+        assert_eq!(alone, vec![true, true, true, true]);
+    }
+}
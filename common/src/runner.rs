@@ -0,0 +1,510 @@
+use std::{
+    fmt,
+    io::BufRead,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use serde_json::{Map, Value};
+
+use crate::{
+    bench::{self, BenchResult, DayBench},
+    generate::{self, Generated, Generator},
+    stream::{StreamError, Streaming},
+    trace, Answer, ParseError, Solution,
+};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Parts {
+    One,
+    Two,
+    Both,
+}
+
+impl Parts {
+    fn includes_part_1(self) -> bool {
+        matches!(self, Parts::One | Parts::Both)
+    }
+
+    fn includes_part_2(self) -> bool {
+        matches!(self, Parts::Two | Parts::Both)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err("Expected --format text or --format json".to_string()),
+        }
+    }
+}
+
+// A day's generator, along with the size of input it makes by default
+type GeneratorFn = (usize, fn(usize, u64) -> Generated);
+
+// A registered day: its number and the monomorphised entry points that parse and solve it
+pub struct Day {
+    pub day: u8,
+    pub memory: usize,
+    run: fn(&str, Parts) -> DayResult,
+    bench: fn(&str, usize) -> Result<DayBench, String>,
+    generator: Option<GeneratorFn>,
+}
+
+impl Day {
+    pub fn of<S: Solution>() -> Day {
+        Day {
+            day: S::DAY,
+            memory: S::MEMORY,
+            run: run::<S>,
+            bench: bench::bench::<S>,
+            generator: None,
+        }
+    }
+
+    // A day which can also generate its own inputs
+    pub fn with_generator<G: Generator>() -> Day {
+        Day {
+            generator: Some((G::DEFAULT_SIZE, generate::generate::<G>)),
+            ..Day::of::<G>()
+        }
+    }
+
+    pub fn can_generate(&self) -> bool {
+        self.generator.is_some()
+    }
+
+    // Generates an input of the given size, or the size of the real input, if the day has a generator
+    pub fn generate(&self, size: Option<usize>, seed: u64) -> Option<Generated> {
+        self.generator
+            .map(|(default_size, generate)| generate(size.unwrap_or(default_size), seed))
+    }
+
+    pub fn run(&self, input: &str, parts: Parts) -> DayResult {
+        (self.run)(input, parts)
+    }
+
+    pub fn bench(&self, input: &str, iterations: usize) -> BenchResult {
+        BenchResult {
+            day: self.day,
+            outcome: (self.bench)(input, iterations),
+        }
+    }
+}
+
+fn run<S: Solution>(input: &str, parts: Parts) -> DayResult {
+    run_with(S::DAY, input, parts, S::parse, S::part1, S::part2)
+}
+
+// Parses and solves a day's input with the given functions in place of those of its `Solution`, for days whose
+// parts depend on more than the input
+pub fn run_with<I>(
+    day: u8,
+    input: &str,
+    parts: Parts,
+    parse: impl FnOnce(&str) -> Result<I, ParseError>,
+    part1: impl FnOnce(&I) -> Answer,
+    part2: impl FnOnce(&I) -> Answer,
+) -> DayResult {
+    let start = Instant::now();
+    let parsed = match trace::within(day, "parse", || parse(input)) {
+        Ok(parsed) => parsed,
+        Err(err) => return DayResult::failed(day, format!("unable to parse input: {}", err)),
+    };
+    let parse = start.elapsed();
+
+    let part_1 = if parts.includes_part_1() {
+        Some(time(|| trace::within(day, "part 1", || part1(&parsed))))
+    } else {
+        None
+    };
+
+    let part_2 = if parts.includes_part_2() {
+        Some(time(|| trace::within(day, "part 2", || part2(&parsed))))
+    } else {
+        None
+    };
+
+    DayResult {
+        day,
+        outcome: Ok(Solved {
+            parse,
+            part_1,
+            part_2,
+        }),
+    }
+}
+
+// Solves a day as its input is read.  Parsing and solving are interleaved, so the whole time is counted as parsing.
+pub fn stream<S: Streaming, R: BufRead>(reader: R) -> DayResult {
+    stream_with(S::DAY, reader, S::solve_stream)
+}
+
+// Solves a day as its input is read with the given function in place of its `Streaming` impl
+pub fn stream_with<R: BufRead>(
+    day: u8,
+    reader: R,
+    solve: impl FnOnce(R) -> Result<(Answer, Answer), StreamError>,
+) -> DayResult {
+    let start = Instant::now();
+    let (part_1, part_2) = match trace::within(day, "stream", || solve(reader)) {
+        Ok(answers) => answers,
+        Err(err) => return DayResult::failed(day, err.to_string()),
+    };
+    let parse = start.elapsed();
+
+    let solved = |answer| {
+        Some(PartResult {
+            answer,
+            elapsed: Duration::default(),
+        })
+    };
+
+    DayResult {
+        day,
+        outcome: Ok(Solved {
+            parse,
+            part_1: solved(part_1),
+            part_2: solved(part_2),
+        }),
+    }
+}
+
+fn time(f: impl FnOnce() -> Answer) -> PartResult {
+    let start = Instant::now();
+    let answer = f();
+    let elapsed = start.elapsed();
+
+    PartResult { answer, elapsed }
+}
+
+#[derive(Debug, Clone)]
+pub struct PartResult {
+    pub answer: Answer,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct Solved {
+    pub parse: Duration,
+    pub part_1: Option<PartResult>,
+    pub part_2: Option<PartResult>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DayResult {
+    pub day: u8,
+    pub outcome: Result<Solved, String>,
+}
+
+impl DayResult {
+    pub fn failed(day: u8, reason: String) -> DayResult {
+        DayResult {
+            day,
+            outcome: Err(reason),
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        match &self.outcome {
+            Ok(solved) => {
+                solved.parse
+                    + solved
+                        .part_1
+                        .iter()
+                        .chain(solved.part_2.iter())
+                        .map(|part| part.elapsed)
+                        .sum::<Duration>()
+            }
+            Err(_) => Duration::default(),
+        }
+    }
+}
+
+fn nanos(duration: Duration) -> Value {
+    (duration.as_nanos() as u64).into()
+}
+
+impl DayResult {
+    // A single line JSON object holding the answers to the parts that were run, and how long each stage took, or
+    // the reason that the day couldn't be solved
+    pub fn to_json(&self) -> String {
+        let mut object = Map::new();
+        object.insert("day".to_string(), self.day.into());
+
+        match &self.outcome {
+            Ok(solved) => {
+                let mut timings = Map::new();
+                timings.insert("parse_ns".to_string(), nanos(solved.parse));
+
+                for (name, part) in [("part1", &solved.part_1), ("part2", &solved.part_2)].iter() {
+                    if let Some(part) = part {
+                        // Answers too large for a JSON integer fall back to being written as strings
+                        let answer = serde_json::to_value(&part.answer)
+                            .unwrap_or_else(|_| part.answer.to_string().into());
+                        object.insert(name.to_string(), answer);
+                        timings.insert(format!("{}_ns", name), nanos(part.elapsed));
+                    }
+                }
+
+                object.insert("timings".to_string(), timings.into());
+            }
+            Err(reason) => {
+                object.insert("error".to_string(), reason.clone().into());
+            }
+        }
+
+        Value::Object(object).to_string()
+    }
+}
+
+pub struct Summary<'a>(pub &'a [DayResult]);
+
+fn format_part(part: &Option<PartResult>) -> (String, String) {
+    match part {
+        Some(part) => (part.answer.to_string(), format_duration(part.elapsed)),
+        None => (String::new(), String::new()),
+    }
+}
+
+pub(crate) fn format_duration(duration: Duration) -> String {
+    let micros = duration.as_micros();
+    if micros < 1_000 {
+        format!("{}µs", micros)
+    } else if micros < 1_000_000 {
+        format!("{:.2}ms", micros as f64 / 1e3)
+    } else {
+        format!("{:.2}s", micros as f64 / 1e6)
+    }
+}
+
+impl fmt::Display for Summary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = [
+            "Day".to_string(),
+            "Parse".to_string(),
+            "Part 1".to_string(),
+            "Time".to_string(),
+            "Part 2".to_string(),
+            "Time".to_string(),
+        ];
+
+        let rows: Vec<Result<[String; 6], String>> = self
+            .0
+            .iter()
+            .map(|result| match &result.outcome {
+                Ok(solved) => {
+                    let (answer_1, elapsed_1) = format_part(&solved.part_1);
+                    let (answer_2, elapsed_2) = format_part(&solved.part_2);
+                    Ok([
+                        result.day.to_string(),
+                        format_duration(solved.parse),
+                        answer_1,
+                        elapsed_1,
+                        answer_2,
+                        elapsed_2,
+                    ])
+                }
+                Err(reason) => Err(reason.clone()),
+            })
+            .collect();
+
+        // Failed days are reported on a single line spanning the answer columns, so they don't contribute to the
+        // column widths
+        let mut widths = [0; 6];
+        for row in std::iter::once(&header).chain(rows.iter().flatten()) {
+            for (width, cell) in widths.iter_mut().zip(row.iter()) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let format_row = |row: &[String]| {
+            row.iter()
+                .zip(widths.iter())
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join(" | ")
+                .trim_end()
+                .to_string()
+        };
+
+        writeln!(f, "{}", format_row(&header))?;
+        for (result, row) in self.0.iter().zip(rows.iter()) {
+            match row {
+                Ok(row) => writeln!(f, "{}", format_row(row))?,
+                Err(reason) => writeln!(
+                    f,
+                    "{:<width$} | error: {}",
+                    result.day,
+                    reason,
+                    width = widths[0]
+                )?,
+            }
+        }
+
+        let total: Duration = self.0.iter().map(|result| result.elapsed()).sum();
+        writeln!(f, "Total: {}", format_duration(total))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        stream::{parse_lines, StreamError},
+        ParseError,
+    };
+
+    struct Doubler;
+
+    impl Solution for Doubler {
+        type Input = i64;
+
+        const DAY: u8 = 1;
+
+        fn parse(input: &str) -> Result<i64, ParseError> {
+            crate::error::parse_number(input, input.trim())
+        }
+
+        fn part1(input: &i64) -> Answer {
+            trace::summary("doubled", |fields| fields.with("input", *input));
+            (input * 2).into()
+        }
+
+        fn part2(input: &i64) -> Answer {
+            trace::step("negated", |fields| fields.with("input", *input));
+            (-input).into()
+        }
+    }
+
+    // Doubles the sum of the lines, as a stream
+    impl Streaming for Doubler {
+        fn solve_stream(reader: impl BufRead) -> Result<(Answer, Answer), StreamError> {
+            let sum = parse_lines(reader, |line| crate::error::parse_number::<i64>(line, line))
+                .sum::<Result<i64, _>>()?;
+
+            Ok(((sum * 2).into(), Answer::Unsolved))
+        }
+    }
+
+    #[test]
+    fn test_run_parts() {
+        let day = Day::of::<Doubler>();
+
+        let result = day.run("21", Parts::Both);
+        let solved = result.outcome.unwrap();
+        assert_eq!(solved.part_1.unwrap().answer, Answer::Number(42));
+        assert_eq!(solved.part_2.unwrap().answer, Answer::Number(-21));
+
+        let result = day.run("21", Parts::Two);
+        let solved = result.outcome.unwrap();
+        assert!(solved.part_1.is_none());
+        assert_eq!(solved.part_2.unwrap().answer, Answer::Number(-21));
+    }
+
+    #[test]
+    fn test_trace() {
+        let day = Day::of::<Doubler>();
+
+        let (_, events) = trace::capture(trace::Level::Steps, || day.run("21", Parts::Both));
+        let stages: Vec<_> = events
+            .iter()
+            .map(|event| (event.day, event.stage, event.name))
+            .collect();
+        assert_eq!(
+            stages,
+            vec![(1, "part 1", "doubled"), (1, "part 2", "negated")]
+        );
+
+        let (_, events) = trace::capture(trace::Level::Summary, || day.run("21", Parts::Two));
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_stream() {
+        let solved = stream::<Doubler, _>("20\n1\n".as_bytes()).outcome.unwrap();
+        assert_eq!(solved.part_1.unwrap().answer, Answer::Number(42));
+        assert_eq!(solved.part_2.unwrap().answer, Answer::Unsolved);
+
+        let result = stream::<Doubler, _>("20\none".as_bytes());
+        assert_eq!(
+            result.outcome.unwrap_err(),
+            "unable to parse input: line 2, column 1: expected a number, found \"one\""
+        );
+    }
+
+    #[test]
+    fn test_run_parse_error() {
+        let result = Day::of::<Doubler>().run("twenty one", Parts::Both);
+        assert_eq!(
+            result.outcome.unwrap_err(),
+            "unable to parse input: line 1, column 1: expected a number, found \"twenty one\""
+        );
+    }
+
+    #[test]
+    fn test_to_json() {
+        let result = DayResult {
+            day: 21,
+            outcome: Ok(Solved {
+                parse: Duration::from_nanos(300),
+                part_1: Some(PartResult {
+                    answer: Answer::Number(5),
+                    elapsed: Duration::from_nanos(500),
+                }),
+                part_2: Some(PartResult {
+                    answer: Answer::Text("mxmxvkd,sqjhc,fvjkl".to_string()),
+                    elapsed: Duration::from_nanos(700),
+                }),
+            }),
+        };
+        assert_eq!(
+            result.to_json(),
+            "{\"day\":21,\"part1\":5,\"part2\":\"mxmxvkd,sqjhc,fvjkl\",\
+             \"timings\":{\"parse_ns\":300,\"part1_ns\":500,\"part2_ns\":700}}"
+        );
+
+        let result = Day::of::<Doubler>().run("21", Parts::One);
+        assert!(result
+            .to_json()
+            .starts_with("{\"day\":1,\"part1\":42,\"timings\":"));
+
+        let result = DayResult::failed(15, "no input".to_string());
+        assert_eq!(result.to_json(), "{\"day\":15,\"error\":\"no input\"}");
+    }
+
+    #[test]
+    fn test_summary() {
+        let results = vec![
+            DayResult {
+                day: 1,
+                outcome: Ok(Solved {
+                    parse: Duration::from_micros(3),
+                    part_1: Some(PartResult {
+                        answer: Answer::Number(42),
+                        elapsed: Duration::from_micros(5),
+                    }),
+                    part_2: None,
+                }),
+            },
+            DayResult::failed(15, "no input".to_string()),
+        ];
+
+        let summary = Summary(&results).to_string();
+        let lines: Vec<_> = summary.lines().collect();
+        assert_eq!(lines[0], "Day | Parse | Part 1 | Time | Part 2 | Time");
+        assert_eq!(lines[1], "1   | 3µs   | 42     | 5µs  |        |");
+        assert_eq!(lines[2], "15  | error: no input");
+        assert_eq!(lines[3], "Total: 8µs");
+    }
+}
@@ -0,0 +1,329 @@
+use std::{env, io::BufRead, path::PathBuf, process, str::FromStr, time::Duration};
+
+use crate::{
+    input::InputSource,
+    runner::{self, Day, DayResult, Format, Parts},
+    stream::{StreamError, Streaming},
+    trace::{self, Event},
+    visualize::{self, Player, Visualize},
+    Answer, ParseError,
+};
+
+// A day's puzzle, split into the stages shared by every day: parsing the raw puzzle input into a typed `Input`, then
+// solving each part from that parsed input.
+pub trait Solution {
+    type Input;
+
+    const DAY: u8;
+
+    // Roughly how many bytes solving the day needs, for deciding which days can be run at the same time.  Only the
+    // days with large tables are worth counting.
+    const MEMORY: usize = 0;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    fn part1(input: &Self::Input) -> Answer;
+
+    fn part2(input: &Self::Input) -> Answer;
+}
+
+// A flag taking a value which only some days accept, along with what the value is called in the usage, as in
+// `--target N`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Flag {
+    pub name: &'static str,
+    pub value: &'static str,
+}
+
+// The values given for a day's own flags
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Flags(Vec<(&'static str, String)>);
+
+impl Flags {
+    // The value last given for the flag, if it was given at all
+    pub fn get<T: FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        match self.0.iter().rev().find(|(flag, _)| *flag == name) {
+            Some((_, value)) => value
+                .parse()
+                .map(Some)
+                .map_err(|_| format!("Unexpected value for {}: {}", name, value)),
+            None => Ok(None),
+        }
+    }
+}
+
+// The individual days' command line arguments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Args {
+    pub format: Format,
+    pub path: Option<PathBuf>,
+    pub stream: bool,
+    pub visualize: Option<visualize::Options>,
+    pub trace: Option<trace::Level>,
+    pub flags: Flags,
+}
+
+// Parses the individual days' command line arguments into the output format, whether to solve the input as it's
+// read or to watch it being solved, how much of the solving to trace, the values of any of `day_flags`, and, if one
+// was given, the path to the input
+pub fn parse_args(args: &[String], day_flags: &[Flag]) -> Result<Args, String> {
+    let mut format = Format::Text;
+    let mut path = None;
+    let mut stream = false;
+    let mut visualize = false;
+    let mut options = visualize::Options::default();
+    let mut trace = None;
+    let mut flags = Flags::default();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if let Some(flag) = day_flags.iter().find(|flag| flag.name == arg) {
+            match args.next() {
+                Some(value) => flags.0.push((flag.name, value.clone())),
+                None => return Err(format!("Expected a value after {}", flag.name)),
+            }
+            continue;
+        }
+
+        match arg.as_str() {
+            "--format" => match args.next() {
+                Some(name) => format = name.parse()?,
+                None => return Err("Expected --format text or --format json".to_string()),
+            },
+            "--stream" => stream = true,
+            "--visualize" => visualize = true,
+            "--delay" => match args.next().map(|s| s.parse::<u64>()) {
+                Some(Ok(ms)) => options.delay = Duration::from_millis(ms),
+                _ => return Err("Expected a delay in milliseconds after --delay".to_string()),
+            },
+            "--paused" => options.paused = true,
+            "--trace" => match args.next() {
+                Some(level) => trace = Some(level.parse()?),
+                None => return Err("Expected --trace summary or --trace steps".to_string()),
+            },
+            _ if path.is_none() => path = Some(PathBuf::from(arg)),
+            _ => return Err(format!("Unexpected argument: {}", arg)),
+        }
+    }
+
+    Ok(Args {
+        format,
+        path,
+        stream,
+        visualize: if visualize { Some(options) } else { None },
+        trace,
+        flags,
+    })
+}
+
+// Prints each part's answer on its own line, skipping parts without an answer, or else a single line of JSON.
+// Failures are printed to stderr as text, but to stdout as JSON so that scripts always have something to read.
+pub fn print_result(result: &DayResult, format: Format) {
+    match (format, &result.outcome) {
+        (Format::Json, _) => println!("{}", result.to_json()),
+        (Format::Text, Ok(solved)) => {
+            for part in solved.part_1.iter().chain(solved.part_2.iter()) {
+                if part.answer != Answer::Unsolved {
+                    println!("{}", part.answer);
+                }
+            }
+        }
+        (Format::Text, Err(reason)) => eprintln!("{}", reason),
+    }
+}
+
+// Prints traced events to stderr, so that they stay out of the way of the answers, as text or as one JSON object
+// per line
+pub fn print_events(events: &[Event], format: Format) {
+    for event in events {
+        match format {
+            Format::Text => eprintln!("{}", event),
+            Format::Json => eprintln!("{}", event.to_json()),
+        }
+    }
+}
+
+// Runs `f`, tracing it at the given level if there is one
+pub fn traced<R>(level: Option<trace::Level>, f: impl FnOnce() -> R) -> (R, Vec<Event>) {
+    match level {
+        Some(level) => trace::capture(level, f),
+        None => (f(), Vec::new()),
+    }
+}
+
+// Entry point for the individual days' binaries, which take the input's path as an argument, falling back to piped
+// stdin and then the usual defaults from `source`
+pub fn main<S: Solution>(source: InputSource) {
+    run_binary::<S>(source, Modes::default(), |_| Ok(Solve::of::<S>()));
+}
+
+// Entry point for the binaries of days which can also be solved as their input is read, given `--stream`
+pub fn main_streaming<S: Streaming + 'static>(source: InputSource) {
+    let modes = Modes {
+        stream: true,
+        ..Modes::default()
+    };
+    run_binary::<S>(source, modes, |_| {
+        Ok(Solve {
+            stream: Some(Box::new(runner::stream::<S, Box<dyn BufRead>>)),
+            ..Solve::of::<S>()
+        })
+    });
+}
+
+// Entry point for the binaries of days whose simulations can be watched, given `--visualize`
+pub fn main_visualized<S: Visualize>(source: InputSource) {
+    let modes = Modes {
+        visualize: Some(watch::<S>),
+        ..Modes::default()
+    };
+    run_binary::<S>(source, modes, |_| Ok(Solve::of::<S>()));
+}
+
+type PartFn<I> = Box<dyn FnOnce(&I) -> Answer>;
+type StreamFn = Box<dyn FnOnce(Box<dyn BufRead>) -> Result<(Answer, Answer), StreamError>>;
+
+// How a day with flags of its own is solved once their values are known, in place of its `Solution` parts and its
+// `Streaming` impl
+pub struct Solver<I> {
+    pub part1: PartFn<I>,
+    pub part2: PartFn<I>,
+    pub stream: StreamFn,
+}
+
+// Entry point for the binaries of days which take flags of their own, such as a different target to look for.
+// `solver` is given the values of the flags and returns how to solve the day with them, or else a usage error.
+pub fn main_with_flags<S: Streaming + 'static>(
+    source: InputSource,
+    flags: &'static [Flag],
+    solver: impl FnOnce(&Flags) -> Result<Solver<S::Input>, String>,
+) {
+    let modes = Modes {
+        flags,
+        stream: true,
+        ..Modes::default()
+    };
+    run_binary::<S>(source, modes, |flags| {
+        let Solver {
+            part1,
+            part2,
+            stream,
+        } = solver(flags)?;
+
+        Ok(Solve {
+            whole: Box::new(move |input| {
+                runner::run_with(S::DAY, input, Parts::Both, S::parse, part1, part2)
+            }),
+            stream: Some(Box::new(move |reader| {
+                runner::stream_with(S::DAY, reader, stream)
+            })),
+        })
+    });
+}
+
+fn watch<S: Visualize>(input: &str, options: visualize::Options) -> Result<(), String> {
+    let input = S::parse(input).map_err(|err| format!("unable to parse input: {}", err))?;
+
+    S::visualize(&input, &mut Player::new(options)).map_err(|err| err.to_string())
+}
+
+type WatchFn = fn(&str, visualize::Options) -> Result<(), String>;
+
+// The ways of running a day other than solving the whole input at once, which only some days support, along with
+// the flags of the day's own that it takes
+#[derive(Default)]
+struct Modes {
+    flags: &'static [Flag],
+    stream: bool,
+    visualize: Option<WatchFn>,
+}
+
+impl Modes {
+    fn usage(&self, day: u8) -> String {
+        let mut flags = String::new();
+        for flag in self.flags {
+            flags.push_str(&format!("[{} {}] ", flag.name, flag.value));
+        }
+        flags.push_str("[--format text|json] [--trace summary|steps]");
+        if self.stream {
+            flags.push_str(" [--stream]");
+        }
+        if self.visualize.is_some() {
+            flags.push_str(" [--visualize [--delay MS] [--paused]]");
+        }
+
+        format!("Usage: day{} {} [PATH|-]", day, flags)
+    }
+
+    fn check(&self, day: u8, args: &Args) -> Result<(), String> {
+        match (args.stream, args.visualize) {
+            (true, Some(_)) => Err("Expected either --stream or --visualize, not both".to_string()),
+            (true, _) if !self.stream => Err(format!("Day {} can't be solved as a stream", day)),
+            (_, Some(_)) if self.visualize.is_none() => {
+                Err(format!("Day {} can't be visualized", day))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+type SolveFn = Box<dyn FnOnce(&str) -> DayResult>;
+type SolveStreamFn = Box<dyn FnOnce(Box<dyn BufRead>) -> DayResult>;
+
+// How a binary solves its input once the day's own flags are known: all at once, and as it's read if it can be
+struct Solve {
+    whole: SolveFn,
+    stream: Option<SolveStreamFn>,
+}
+
+impl Solve {
+    fn of<S: Solution>() -> Solve {
+        Solve {
+            whole: Box::new(|input| Day::of::<S>().run(input, Parts::Both)),
+            stream: None,
+        }
+    }
+}
+
+fn run_binary<S: Solution>(
+    source: InputSource,
+    modes: Modes,
+    solve: impl FnOnce(&Flags) -> Result<Solve, String>,
+) {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (args, solve) = parse_args(&args, modes.flags)
+        .and_then(|args| modes.check(S::DAY, &args).map(|_| args))
+        .and_then(|args| solve(&args.flags).map(|solve| (args, solve)))
+        .unwrap_or_else(|err| {
+            eprintln!("{}\n\n{}", err, modes.usage(S::DAY));
+            process::exit(2);
+        });
+
+    let source = source.path(args.path.clone()).piped_stdin();
+    if let (Some(watch), Some(options)) = (modes.visualize, args.visualize) {
+        let watched = source.read().map_err(|err| err.to_string());
+        if let Err(err) = watched.and_then(|input| watch(&input, options)) {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+        return;
+    }
+
+    let Solve { whole, stream } = solve;
+    let (result, events) = traced(args.trace, || match stream.filter(|_| args.stream) {
+        Some(stream) => match source.open() {
+            Ok(reader) => stream(reader),
+            Err(err) => DayResult::failed(S::DAY, err.to_string()),
+        },
+        None => match source.read() {
+            Ok(input) => whole(&input),
+            Err(err) => DayResult::failed(S::DAY, err.to_string()),
+        },
+    });
+
+    print_events(&events, args.format);
+    print_result(&result, args.format);
+    if result.outcome.is_err() {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,81 @@
+use std::{
+    error, fmt,
+    io::{self, BufRead},
+};
+
+use crate::{Answer, ParseError, Solution};
+
+// An error found while reading input a line at a time, which might not be the input's fault
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(err) => write!(f, "unable to read input: {}", err),
+            StreamError::Parse(err) => write!(f, "unable to parse input: {}", err),
+        }
+    }
+}
+
+impl error::Error for StreamError {}
+
+impl From<io::Error> for StreamError {
+    fn from(err: io::Error) -> Self {
+        StreamError::Io(err)
+    }
+}
+
+impl From<ParseError> for StreamError {
+    fn from(err: ParseError) -> Self {
+        StreamError::Parse(err)
+    }
+}
+
+// A day which can be solved as its input is read, a line at a time, without holding on to the whole input.  Parts
+// which need the whole input are left unsolved.
+pub trait Streaming: Solution {
+    fn solve_stream(reader: impl BufRead) -> Result<(Answer, Answer), StreamError>;
+}
+
+// Parses each line of `reader` as it's read, with any error placed at its line within the whole input
+pub fn parse_lines<R, T, F>(reader: R, mut parse: F) -> impl Iterator<Item = Result<T, StreamError>>
+where
+    R: BufRead,
+    F: FnMut(&str) -> Result<T, ParseError>,
+{
+    reader.lines().enumerate().map(move |(idx, line)| {
+        let line = line?;
+        parse(&line).map_err(|err| {
+            StreamError::Parse(ParseError {
+                line: err.line + idx,
+                ..err
+            })
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::parse_number;
+
+    #[test]
+    fn test_parse_lines() {
+        let parse = |input: &'static str| {
+            parse_lines(input.as_bytes(), |line| parse_number::<u32>(line, line))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        assert_eq!(parse("1\r\n2\n3\n").unwrap(), vec![1, 2, 3]);
+        assert!(parse("").unwrap().is_empty());
+
+        match parse("1\n2\nx\n4") {
+            Err(StreamError::Parse(err)) => assert_eq!((err.line, err.column), (3, 1)),
+            result => panic!("expected a parse error, found {:?}", result),
+        }
+    }
+}
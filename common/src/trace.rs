@@ -0,0 +1,245 @@
+use std::{cell::RefCell, fmt, str::FromStr};
+
+use serde_json::{Map, Value};
+
+// How much detail is traced: just what each part found, or every step taken to find it too
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Summary,
+    Steps,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Level::Summary => write!(f, "summary"),
+            Level::Steps => write!(f, "steps"),
+        }
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "summary" => Ok(Level::Summary),
+            "steps" => Ok(Level::Steps),
+            _ => Err("Expected --trace summary or --trace steps".to_string()),
+        }
+    }
+}
+
+// The named values describing an event, kept in the order they were given
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Fields(Vec<(&'static str, Value)>);
+
+impl Fields {
+    pub fn with(mut self, name: &'static str, value: impl Into<Value>) -> Fields {
+        self.0.push((name, value.into()));
+        self
+    }
+}
+
+// Something a solver did, along with the day and stage (parsing or one of the parts) it happened in
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub day: u8,
+    pub stage: &'static str,
+    pub level: Level,
+    pub name: &'static str,
+    pub fields: Fields,
+}
+
+impl Event {
+    // A single line of JSON, with the fields in an object of their own so that they can't clash with the event's
+    // keys
+    pub fn to_json(&self) -> String {
+        let fields: Map<String, Value> = self
+            .fields
+            .0
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect();
+
+        let mut object = Map::new();
+        object.insert("day".to_string(), self.day.into());
+        object.insert("stage".to_string(), self.stage.into());
+        object.insert("level".to_string(), self.level.to_string().into());
+        object.insert("event".to_string(), self.name.into());
+        object.insert("fields".to_string(), fields.into());
+
+        Value::Object(object).to_string()
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "day {} {}: {}", self.day, self.stage, self.name)?;
+        for (idx, (name, value)) in self.fields.0.iter().enumerate() {
+            let separator = if idx == 0 { " " } else { ", " };
+            write!(f, "{}{}={}", separator, name, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+struct Tracer {
+    level: Level,
+    day: u8,
+    stage: &'static str,
+    events: Vec<Event>,
+}
+
+thread_local! {
+    static TRACER: RefCell<Option<Tracer>> = const { RefCell::new(None) };
+}
+
+// Puts back whichever tracer was in place before, even if the traced code panicked
+struct Restore(Option<Tracer>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        TRACER.with(|tracer| *tracer.borrow_mut() = self.0.take());
+    }
+}
+
+// Runs `f`, collecting the events up to `level` that are emitted on this thread while it runs
+pub fn capture<R>(level: Level, f: impl FnOnce() -> R) -> (R, Vec<Event>) {
+    let tracer = Tracer {
+        level,
+        day: 0,
+        stage: "",
+        events: Vec::new(),
+    };
+    let restore = Restore(TRACER.with(|current| current.borrow_mut().replace(tracer)));
+
+    let result = f();
+    let events = TRACER.with(|current| current.borrow_mut().take().map(|tracer| tracer.events));
+    drop(restore);
+
+    (result, events.unwrap_or_default())
+}
+
+// Marks the events emitted by `f` as coming from the given day and stage
+pub fn within<R>(day: u8, stage: &'static str, f: impl FnOnce() -> R) -> R {
+    let outer = TRACER.with(|current| {
+        current.borrow_mut().as_mut().map(|tracer| {
+            let outer = (tracer.day, tracer.stage);
+            tracer.day = day;
+            tracer.stage = stage;
+            outer
+        })
+    });
+
+    let result = f();
+    if let Some((day, stage)) = outer {
+        TRACER.with(|current| {
+            if let Some(tracer) = current.borrow_mut().as_mut() {
+                tracer.day = day;
+                tracer.stage = stage;
+            }
+        });
+    }
+
+    result
+}
+
+// Whether events at `level` are being collected, for skipping any work done only to trace them
+pub fn enabled(level: Level) -> bool {
+    TRACER.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .is_some_and(|tracer| level <= tracer.level)
+    })
+}
+
+// Emits an event, with its fields only built if it's being collected
+pub fn emit(level: Level, name: &'static str, fields: impl FnOnce(Fields) -> Fields) {
+    if !enabled(level) {
+        return;
+    }
+
+    let fields = fields(Fields::default());
+    TRACER.with(|current| {
+        if let Some(tracer) = current.borrow_mut().as_mut() {
+            tracer.events.push(Event {
+                day: tracer.day,
+                stage: tracer.stage,
+                level,
+                name,
+                fields,
+            });
+        }
+    });
+}
+
+pub fn summary(name: &'static str, fields: impl FnOnce(Fields) -> Fields) {
+    emit(Level::Summary, name, fields);
+}
+
+pub fn step(name: &'static str, fields: impl FnOnce(Fields) -> Fields) {
+    emit(Level::Steps, name, fields);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve() -> u32 {
+        within(7, "part 1", || {
+            for bag in ["light red", "bright white"].iter() {
+                step("visit", |fields| fields.with("bag", *bag));
+            }
+            summary("found", |fields| {
+                fields.with("count", 2).with("exact", true)
+            });
+            2
+        })
+    }
+
+    #[test]
+    fn test_capture() {
+        let (answer, events) = capture(Level::Steps, solve);
+        assert_eq!(answer, 2);
+        assert_eq!(
+            events
+                .iter()
+                .map(|event| event.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "day 7 part 1: visit bag=\"light red\"",
+                "day 7 part 1: visit bag=\"bright white\"",
+                "day 7 part 1: found count=2, exact=true",
+            ]
+        );
+        assert_eq!(
+            events[2].to_json(),
+            r#"{"day":7,"event":"found","fields":{"count":2,"exact":true},"level":"summary","stage":"part 1"}"#
+        );
+
+        let (_, events) = capture(Level::Summary, solve);
+        assert_eq!(events.len(), 1);
+
+        assert!(!enabled(Level::Summary));
+        assert_eq!(solve(), 2);
+    }
+
+    #[test]
+    fn test_nested_capture() {
+        let (inner, outer) = capture(Level::Summary, || {
+            summary("before", |fields| fields);
+            let (_, inner) = capture(Level::Steps, || step("inner", |fields| fields));
+            summary("after", |fields| fields);
+            inner
+        });
+
+        assert_eq!(inner.len(), 1);
+        assert_eq!(
+            outer.iter().map(|event| event.name).collect::<Vec<_>>(),
+            vec!["before", "after"]
+        );
+    }
+}
@@ -0,0 +1,429 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    hash::Hash,
+    io::{self, BufRead, IsTerminal, Write},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+use crate::{automaton::Automaton, grid::Point, Solution};
+
+// Where a cell is drawn: the panel it's in, for spaces of more than two dimensions, and its row and column within
+// that panel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placement {
+    pub panel: Vec<i64>,
+    pub row: i64,
+    pub col: i64,
+}
+
+// How the cells of a space are laid out on the terminal
+pub trait Layout<P> {
+    fn place(&self, p: &P) -> Placement;
+
+    // The heading drawn above a panel
+    fn label(&self, _panel: &[i64]) -> String {
+        String::new()
+    }
+
+    // Whether a position is a cell at all, rather than the space between cells
+    fn is_cell(&self, _row: i64, _col: i64) -> bool {
+        true
+    }
+}
+
+// A single grid of (x, y) points
+pub struct Flat;
+
+impl Layout<Point> for Flat {
+    fn place(&self, &(x, y): &Point) -> Placement {
+        Placement {
+            panel: Vec::new(),
+            row: y as i64,
+            col: x as i64,
+        }
+    }
+}
+
+// A panel for each slice through the first two dimensions, e.g. one for each z in three dimensions
+pub struct Slices;
+
+impl<const D: usize> Layout<[i64; D]> for Slices {
+    fn place(&self, p: &[i64; D]) -> Placement {
+        Placement {
+            panel: p[2..].to_vec(),
+            row: p[1],
+            col: p[0],
+        }
+    }
+
+    fn label(&self, panel: &[i64]) -> String {
+        let names = ["z", "w"];
+        panel
+            .iter()
+            .enumerate()
+            .map(|(idx, coordinate)| match names.get(idx) {
+                Some(name) => format!("{}={}", name, coordinate),
+                None => format!("x{}={}", idx + 3, coordinate),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+// The characters for active cells, for inactive cells, and for anything outside the automaton's universe
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Glyphs {
+    pub active: char,
+    pub inactive: char,
+    pub outside: char,
+}
+
+impl Default for Glyphs {
+    fn default() -> Self {
+        Glyphs {
+            active: '#',
+            inactive: '.',
+            outside: '.',
+        }
+    }
+}
+
+// Draws the cells of a universe, or just the active cells if there isn't one, within the smallest rectangle holding
+// them all.  Every panel is drawn over the same rectangle, so that they line up.
+pub fn draw<P, L>(
+    layout: &L,
+    glyphs: Glyphs,
+    active: &HashSet<P>,
+    universe: Option<&HashSet<P>>,
+) -> String
+where
+    P: Eq + Hash,
+    L: Layout<P>,
+{
+    let mut panels: BTreeMap<Vec<i64>, HashMap<(i64, i64), char>> = BTreeMap::new();
+    let inactive = universe
+        .into_iter()
+        .flatten()
+        .filter(|p| !active.contains(p));
+    for (p, glyph) in active
+        .iter()
+        .map(|p| (p, glyphs.active))
+        .chain(inactive.map(|p| (p, glyphs.inactive)))
+    {
+        let placement = layout.place(p);
+        panels
+            .entry(placement.panel)
+            .or_default()
+            .insert((placement.row, placement.col), glyph);
+    }
+
+    let positions = || panels.values().flat_map(|cells| cells.keys());
+    let (rows, cols) = match (
+        positions().map(|&(row, _)| row).min(),
+        positions().map(|&(row, _)| row).max(),
+        positions().map(|&(_, col)| col).min(),
+        positions().map(|&(_, col)| col).max(),
+    ) {
+        (Some(top), Some(bottom), Some(left), Some(right)) => (top..=bottom, left..=right),
+        _ => return String::new(),
+    };
+    let background = if universe.is_some() {
+        glyphs.outside
+    } else {
+        glyphs.inactive
+    };
+
+    let mut drawn = Vec::new();
+    for (panel, cells) in panels.iter() {
+        let mut lines = Vec::new();
+        let label = layout.label(panel);
+        if !label.is_empty() {
+            lines.push(label);
+        }
+
+        for row in rows.clone() {
+            let line: String = cols
+                .clone()
+                .map(|col| match cells.get(&(row, col)) {
+                    Some(&glyph) => glyph,
+                    None if layout.is_cell(row, col) => background,
+                    None => ' ',
+                })
+                .collect();
+            lines.push(line.trim_end().to_string());
+        }
+        drawn.push(lines.join("\n"));
+    }
+
+    drawn.join("\n\n")
+}
+
+// How a simulation is played: the delay between generations, and whether to start paused
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    pub delay: Duration,
+    pub paused: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            delay: Duration::from_millis(200),
+            paused: false,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Control {
+    Pause,
+    Step,
+    Quit,
+}
+
+// Reads controls from a terminal a line at a time: a blank line pauses or resumes, "n" steps a single generation
+// while paused and "q" quits
+fn read_controls() -> Receiver<Control> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let control = match line.as_ref().map(|line| line.trim()) {
+                Ok("") => Control::Pause,
+                Ok("n") => Control::Step,
+                Ok("q") | Err(_) => Control::Quit,
+                Ok(_) => continue,
+            };
+            if sender.send(control).is_err() || control == Control::Quit {
+                break;
+            }
+        }
+    });
+
+    receiver
+}
+
+// Plays an automaton a generation at a time, redrawing the terminal for each
+pub struct Player {
+    options: Options,
+    controls: Option<Receiver<Control>>,
+    out: Box<dyn Write>,
+    clear: bool,
+}
+
+impl Player {
+    // Draws to stdout, which is cleared before each generation if it's a terminal, with the controls read from stdin
+    // if that's a terminal
+    pub fn new(options: Options) -> Player {
+        let interactive = io::stdin().is_terminal();
+
+        Player {
+            options,
+            controls: if interactive {
+                Some(read_controls())
+            } else {
+                None
+            },
+            out: Box::new(io::stdout()),
+            clear: io::stdout().is_terminal(),
+        }
+    }
+
+    // Writes every generation to `out` in turn, without waiting between them
+    pub fn to_writer(out: impl Write + 'static) -> Player {
+        Player {
+            options: Options {
+                delay: Duration::default(),
+                paused: false,
+            },
+            controls: None,
+            out: Box::new(out),
+            clear: false,
+        }
+    }
+
+    fn show(&mut self, heading: &str, frame: &str) -> io::Result<()> {
+        if self.clear {
+            write!(self.out, "\x1b[2J\x1b[H")?;
+        }
+        writeln!(self.out, "{}\n{}\n", heading, frame)?;
+        if self.controls.is_some() {
+            writeln!(
+                self.out,
+                "[Enter] pause or resume, [n Enter] step, [q Enter] quit"
+            )?;
+        }
+
+        self.out.flush()
+    }
+
+    // Waits until the next generation is due, returning false if the player has been told to quit
+    fn wait(&mut self) -> bool {
+        let controls = match &self.controls {
+            Some(controls) => controls,
+            None => {
+                thread::sleep(self.options.delay);
+                return true;
+            }
+        };
+
+        if !self.options.paused {
+            match controls.recv_timeout(self.options.delay) {
+                Ok(Control::Pause) => self.options.paused = true,
+                Ok(Control::Step) | Err(RecvTimeoutError::Timeout) => return true,
+                Ok(Control::Quit) => return false,
+                Err(RecvTimeoutError::Disconnected) => {
+                    thread::sleep(self.options.delay);
+                    return true;
+                }
+            }
+        }
+
+        match controls.recv() {
+            Ok(Control::Pause) => {
+                self.options.paused = false;
+                true
+            }
+            Ok(Control::Step) | Err(_) => true,
+            Ok(Control::Quit) => false,
+        }
+    }
+
+    // Shows each generation of `automaton` until `generations` have passed, or until it's stable if there's no
+    // limit.  Returns false if the player was told to quit part way.
+    pub fn watch<P, N, I, L>(
+        &mut self,
+        title: &str,
+        automaton: &mut Automaton<P, N>,
+        layout: &L,
+        glyphs: Glyphs,
+        generations: Option<usize>,
+    ) -> io::Result<bool>
+    where
+        P: Copy + Eq + Hash,
+        N: Fn(P) -> I,
+        I: IntoIterator<Item = P>,
+        L: Layout<P>,
+    {
+        loop {
+            let heading = format!(
+                "{}: generation {}, {} active",
+                title,
+                automaton.generation(),
+                automaton.population()
+            );
+            let frame = draw(layout, glyphs, automaton.active(), automaton.universe());
+            self.show(&heading, &frame)?;
+
+            if generations.is_some_and(|generations| automaton.generation() >= generations) {
+                return Ok(true);
+            }
+            if !self.wait() {
+                return Ok(false);
+            }
+            if !automaton.step() && generations.is_none() {
+                return Ok(true);
+            }
+        }
+    }
+}
+
+// A day whose simulation can be watched a generation at a time
+pub trait Visualize: Solution {
+    fn visualize(input: &Self::Input, player: &mut Player) -> io::Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automaton::{moore, Rule};
+    use std::sync::{Arc, Mutex};
+
+    // A hexagonal layout where each row is offset by half a cell from the one above
+    struct Hex;
+
+    impl Layout<(i64, i64)> for Hex {
+        fn place(&self, &(u, v): &(i64, i64)) -> Placement {
+            Placement {
+                panel: Vec::new(),
+                row: -v,
+                col: 2 * u + v,
+            }
+        }
+
+        fn is_cell(&self, row: i64, col: i64) -> bool {
+            (row + col).rem_euclid(2) == 0
+        }
+    }
+
+    #[test]
+    fn test_draw() {
+        let active: HashSet<Point> = vec![(1, 0), (2, 1)].into_iter().collect();
+        let seats: HashSet<Point> = vec![(0, 0), (1, 0), (2, 1)].into_iter().collect();
+        let glyphs = Glyphs {
+            active: '#',
+            inactive: 'L',
+            outside: '.',
+        };
+
+        assert_eq!(draw(&Flat, glyphs, &active, Some(&seats)), "L#.\n..#");
+        assert_eq!(draw(&Flat, Glyphs::default(), &active, None), "#.\n.#");
+        assert_eq!(draw(&Flat, glyphs, &HashSet::new(), None), "");
+    }
+
+    #[test]
+    fn test_draw_slices() {
+        let active: HashSet<[i64; 3]> =
+            vec![[0, 0, 0], [1, 1, 0], [1, 0, -1]].into_iter().collect();
+
+        assert_eq!(
+            draw(&Slices, Glyphs::default(), &active, None),
+            "z=-1\n.#\n..\n\nz=0\n#.\n.#"
+        );
+    }
+
+    #[test]
+    fn test_draw_hex() {
+        let active: HashSet<(i64, i64)> = vec![(0, 0), (1, 0), (0, -1)].into_iter().collect();
+
+        assert_eq!(draw(&Hex, Glyphs::default(), &active, None), " # #\n# .");
+    }
+
+    // Collects what's written, so that it can be read back after the player is done with it
+    #[derive(Clone, Default)]
+    struct Recording(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Recording {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_watch() {
+        let recording = Recording::default();
+        let mut player = Player::to_writer(recording.clone());
+        let mut blinker = Automaton::new(
+            vec![[0, -1], [0, 0], [0, 1]],
+            moore,
+            Rule::new(&[3], &[2, 3]),
+        );
+
+        assert!(player
+            .watch("Blinker", &mut blinker, &Slices, Glyphs::default(), Some(2))
+            .unwrap());
+
+        let written = String::from_utf8(recording.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(
+            written,
+            "Blinker: generation 0, 3 active\n#\n#\n#\n\n\
+             Blinker: generation 1, 3 active\n###\n\n\
+             Blinker: generation 2, 3 active\n#\n#\n#\n\n"
+        );
+    }
+}
@@ -0,0 +1,673 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    io::BufRead,
+    ops::ControlFlow,
+};
+
+use common::{
+    error::{parse_lines, parse_number},
+    generate::{Generated, Generator, Rng, SliceRandom, StdRng},
+    stream::{self, StreamError, Streaming},
+    trace, Answer, ParseError, Solution,
+};
+
+// Calls `visit` with each set of `k` distinct indices whose values sum to `target`, until it breaks.  Small k are
+// found by hashing the values seen so far, and larger k by fixing all but two of the values, smallest first, then
+// closing in on the last two from either end of the rest.
+fn search(
+    values: &[i64],
+    k: usize,
+    target: i64,
+    visit: &mut dyn FnMut(Vec<usize>) -> ControlFlow<()>,
+) -> ControlFlow<()> {
+    match k {
+        0 if target == 0 => visit(Vec::new()),
+        0 => ControlFlow::Continue(()),
+        1 => {
+            for (idx, &value) in values.iter().enumerate() {
+                if value == target {
+                    visit(vec![idx])?;
+                }
+            }
+            ControlFlow::Continue(())
+        }
+        2 => {
+            // Each value is only looked for among those before it, so a lone half of the target isn't its own pair
+            let mut seen: HashMap<i128, Vec<usize>> = HashMap::new();
+            for (j, &value) in values.iter().enumerate() {
+                for &i in seen
+                    .get(&(target as i128 - value as i128))
+                    .into_iter()
+                    .flatten()
+                {
+                    visit(vec![i, j])?;
+                }
+                seen.entry(value as i128).or_default().push(j);
+            }
+            ControlFlow::Continue(())
+        }
+        _ => {
+            let mut order: Vec<usize> = (0..values.len()).collect();
+            order.sort_by_key(|&idx| (values[idx], idx));
+            let sorted: Vec<i128> = order.iter().map(|&idx| values[idx] as i128).collect();
+
+            let mut fixed = Vec::with_capacity(k);
+            fix(
+                &sorted,
+                k,
+                target as i128,
+                0,
+                &mut fixed,
+                &mut |positions| {
+                    let mut indices: Vec<usize> = positions.iter().map(|&p| order[p]).collect();
+                    indices.sort_unstable();
+                    visit(indices)
+                },
+            )
+        }
+    }
+}
+
+// Fixes the next of the `k` smallest-first positions into `sorted` at or after `from`, with the last two found
+// together
+fn fix(
+    sorted: &[i128],
+    k: usize,
+    target: i128,
+    from: usize,
+    fixed: &mut Vec<usize>,
+    visit: &mut dyn FnMut(&[usize]) -> ControlFlow<()>,
+) -> ControlFlow<()> {
+    if k == 2 {
+        return close_in(sorted, target, from, fixed, visit);
+    }
+
+    for p in from..sorted.len() {
+        if p + k > sorted.len() || sorted[p..p + k].iter().sum::<i128>() > target {
+            break;
+        }
+
+        fixed.push(p);
+        let flow = fix(sorted, k - 1, target - sorted[p], p + 1, fixed, visit);
+        fixed.pop();
+        flow?;
+    }
+
+    ControlFlow::Continue(())
+}
+
+// The pairs of positions at or after `from` in `sorted` summing to `target`.  Runs of equal values are paired up
+// in every way, so that duplicates make as many solutions as there are ways of choosing them.
+fn close_in(
+    sorted: &[i128],
+    target: i128,
+    from: usize,
+    fixed: &mut Vec<usize>,
+    visit: &mut dyn FnMut(&[usize]) -> ControlFlow<()>,
+) -> ControlFlow<()> {
+    let mut pair = |fixed: &mut Vec<usize>, lo: usize, hi: usize| {
+        fixed.push(lo);
+        fixed.push(hi);
+        let flow = visit(fixed);
+        fixed.truncate(fixed.len() - 2);
+        flow
+    };
+
+    if sorted.len() < from + 2 {
+        return ControlFlow::Continue(());
+    }
+    let (mut lo, mut hi) = (from, sorted.len() - 1);
+    while lo < hi {
+        let sum = sorted[lo] + sorted[hi];
+        if sum < target {
+            lo += 1;
+        } else if sum > target {
+            hi -= 1;
+        } else if sorted[lo] == sorted[hi] {
+            for a in lo..hi {
+                for b in a + 1..=hi {
+                    pair(fixed, a, b)?;
+                }
+            }
+            break;
+        } else {
+            let lo_end = lo
+                + sorted[lo..]
+                    .iter()
+                    .take_while(|&&x| x == sorted[lo])
+                    .count();
+            let hi_start = hi + 1
+                - sorted[..=hi]
+                    .iter()
+                    .rev()
+                    .take_while(|&&x| x == sorted[hi])
+                    .count();
+            for a in lo..lo_end {
+                for b in hi_start..=hi {
+                    pair(fixed, a, b)?;
+                }
+            }
+            lo = lo_end;
+            hi = hi_start - 1;
+        }
+    }
+
+    ControlFlow::Continue(())
+}
+
+// Every set of `k` distinct indices whose values sum to `target`, each with its indices in increasing order.  The
+// same value appearing twice can be used twice, but a value appearing once can only be used once.
+pub fn k_sum(values: &[i64], k: usize, target: i64) -> Vec<Vec<usize>> {
+    let mut solutions = Vec::new();
+    let _ = search(values, k, target, &mut |indices| {
+        solutions.push(indices);
+        ControlFlow::Continue(())
+    });
+    solutions.sort();
+
+    solutions
+}
+
+// A single set of `k` distinct indices whose values sum to `target`, without looking for any others
+pub fn first_k_sum(values: &[i64], k: usize, target: i64) -> Option<Vec<usize>> {
+    let mut solution = None;
+    let _ = search(values, k, target, &mut |indices| {
+        solution = Some(indices);
+        ControlFlow::Break(())
+    });
+
+    solution
+}
+
+// The number of sets of `k` distinct indices whose values sum to `target`
+pub fn count_k_sum(values: &[i64], k: usize, target: i64) -> usize {
+    let mut count = 0;
+    let _ = search(values, k, target, &mut |_| {
+        count += 1;
+        ControlFlow::Continue(())
+    });
+
+    count
+}
+
+// The solutions for each of the targets, with targets that have no solutions left out
+pub fn k_sum_targets(values: &[i64], k: usize, targets: &[i64]) -> BTreeMap<i64, Vec<Vec<usize>>> {
+    let targets: BTreeSet<i64> = targets.iter().copied().collect();
+
+    targets
+        .into_iter()
+        .map(|target| (target, k_sum(values, k, target)))
+        .filter(|(_, solutions)| !solutions.is_empty())
+        .collect()
+}
+
+// The `k` distinct indices whose values sum closest to `target` without going over it, along with that sum.  Like
+// the k-sum search, all but two values are fixed smallest first, and the best of the last two is closed in on from
+// either end of the rest.
+pub fn closest_k_sum(values: &[i64], k: usize, target: i64) -> Option<(i64, Vec<usize>)> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by_key(|&idx| (values[idx], idx));
+    let sorted: Vec<i128> = order.iter().map(|&idx| values[idx] as i128).collect();
+
+    let mut best: Option<(i128, Vec<usize>)> = None;
+    let mut fixed = Vec::with_capacity(k);
+    closest(&sorted, k, target as i128, 0, &mut fixed, &mut best);
+
+    best.map(|(sum, positions)| {
+        let mut indices: Vec<usize> = positions.iter().map(|&p| order[p]).collect();
+        indices.sort_unstable();
+        (sum as i64, indices)
+    })
+}
+
+// Improves on `best` with sums of `k` more positions at or after `from` in `sorted`, added to those already fixed
+fn closest(
+    sorted: &[i128],
+    k: usize,
+    target: i128,
+    from: usize,
+    fixed: &mut Vec<usize>,
+    best: &mut Option<(i128, Vec<usize>)>,
+) {
+    let fixed_sum: i128 = fixed.iter().map(|&p| sorted[p]).sum();
+    let mut improve = |sum: i128, positions: &[usize]| {
+        if sum <= target && best.as_ref().is_none_or(|(best, _)| sum > *best) {
+            let mut solution = fixed.clone();
+            solution.extend_from_slice(positions);
+            *best = Some((sum, solution));
+        }
+    };
+
+    match k {
+        0 => improve(fixed_sum, &[]),
+        1 => {
+            // The largest value which fits
+            let fits = sorted[from..].partition_point(|&x| fixed_sum + x <= target);
+            if fits > 0 {
+                let p = from + fits - 1;
+                improve(fixed_sum + sorted[p], &[p]);
+            }
+        }
+        2 => {
+            if sorted.len() < from + 2 {
+                return;
+            }
+            let (mut lo, mut hi) = (from, sorted.len() - 1);
+            while lo < hi {
+                let sum = fixed_sum + sorted[lo] + sorted[hi];
+                if sum <= target {
+                    improve(sum, &[lo, hi]);
+                    lo += 1;
+                } else {
+                    hi -= 1;
+                }
+            }
+        }
+        _ => {
+            for p in from..sorted.len() {
+                if p + k > sorted.len()
+                    || fixed_sum + sorted[p..p + k].iter().sum::<i128>() > target
+                {
+                    break;
+                }
+
+                fixed.push(p);
+                closest(sorted, k - 1, target, p + 1, fixed, best);
+                fixed.pop();
+            }
+        }
+    }
+}
+
+// The product of `k` entries summing to `target`, which is None if there aren't any or if the product is too big
+// even for an `i128`
+fn product_of_k_sum(values: &[i64], k: usize, target: i64) -> Option<i128> {
+    let indices = first_k_sum(values, k, target)?;
+    let entries: Vec<i64> = indices.iter().map(|&idx| values[idx]).collect();
+    trace::summary("solution", |fields| {
+        fields
+            .with("k", k)
+            .with(
+                "lines",
+                indices.iter().map(|idx| idx + 1).collect::<Vec<_>>(),
+            )
+            .with("entries", entries.clone())
+    });
+
+    entries
+        .iter()
+        .try_fold(1i128, |product, &entry| product.checked_mul(entry as i128))
+}
+
+pub fn two_sum_product(values: &[i64], target: i64) -> Option<i128> {
+    product_of_k_sum(values, 2, target)
+}
+
+pub fn three_sum_product(values: &[i64], target: i64) -> Option<i128> {
+    product_of_k_sum(values, 3, target)
+}
+
+// Looks for the pair and the triple summing to the target as the entries arrive.  Entries are never negative, so
+// only those of at most the target can be part of a solution, and only pairs summing to at most the target need
+// remembering, which keeps the memory used bounded however many entries there are.  Products are kept as `i128`s,
+// and a triple whose product doesn't fit even so is passed over.
+#[derive(Debug, Clone)]
+pub struct ExpenseScan {
+    target: i64,
+    seen: HashSet<i64>,
+    pair_products: HashMap<i64, i128>,
+    pub two_sum_product: Option<i128>,
+    pub three_sum_product: Option<i128>,
+}
+
+impl ExpenseScan {
+    pub fn new(target: i64) -> ExpenseScan {
+        ExpenseScan {
+            target,
+            seen: HashSet::new(),
+            pair_products: HashMap::new(),
+            two_sum_product: None,
+            three_sum_product: None,
+        }
+    }
+
+    pub fn push(&mut self, value: i64) {
+        let target = self.target;
+        if !(0..=target).contains(&value) {
+            return;
+        }
+
+        if self.two_sum_product.is_none() && self.seen.contains(&(target - value)) {
+            trace::summary("pair", |fields| {
+                fields.with("entries", vec![target - value, value])
+            });
+            self.two_sum_product = Some(value as i128 * (target - value) as i128);
+        }
+
+        if self.three_sum_product.is_none() {
+            let triple = self
+                .pair_products
+                .get(&(target - value))
+                .map(|product| product.checked_mul(value as i128));
+            match triple {
+                Some(Some(product)) => {
+                    trace::summary("triple", |fields| {
+                        fields
+                            .with("last", value)
+                            .with("product", product.to_string())
+                    });
+                    self.three_sum_product = Some(product)
+                }
+                _ => {
+                    // Written so as not to overflow when the target is near the largest `i64`
+                    for &seen in self.seen.iter().filter(|&&seen| seen <= target - value) {
+                        self.pair_products
+                            .entry(seen + value)
+                            .or_insert(seen as i128 * value as i128);
+                    }
+                }
+            }
+        }
+
+        self.seen.insert(value);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.two_sum_product.is_some() && self.three_sum_product.is_some()
+    }
+}
+
+// Scans entries as they're read, stopping as soon as both the pair and the triple have been found
+pub fn scan_stream(reader: impl BufRead, target: i64) -> Result<ExpenseScan, StreamError> {
+    let mut scan = ExpenseScan::new(target);
+    for value in stream::parse_lines(reader, parse_entry) {
+        scan.push(value?);
+        if scan.is_finished() {
+            break;
+        }
+    }
+
+    Ok(scan)
+}
+
+// An entry, which unlike when the whole input is parsed at once mustn't be negative
+fn parse_entry(line: &str) -> Result<i64, ParseError> {
+    match parse_number(line, line)? {
+        value if value < 0 => Err(ParseError::new(line, line, "a non-negative number")),
+        value => Ok(value),
+    }
+}
+
+// What the entries of the expense report are meant to sum to
+pub const TARGET: i64 = 2020;
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<i64>;
+
+    const DAY: u8 = 1;
+
+    fn parse(input: &str) -> Result<Vec<i64>, ParseError> {
+        parse_lines(input, |line| parse_number(line, line))
+    }
+
+    fn part1(values: &Vec<i64>) -> Answer {
+        two_sum_product(values, TARGET).into()
+    }
+
+    fn part2(values: &Vec<i64>) -> Answer {
+        three_sum_product(values, TARGET).into()
+    }
+}
+
+impl Streaming for Day1 {
+    fn solve_stream(reader: impl BufRead) -> Result<(Answer, Answer), StreamError> {
+        let scan = scan_stream(reader, TARGET)?;
+
+        Ok((scan.two_sum_product.into(), scan.three_sum_product.into()))
+    }
+}
+
+// The number of pairs and of triples summing to `TARGET` which adding `value` to `values` would make
+fn solutions_with(values: &HashSet<i64>, value: i64) -> (usize, usize) {
+    let pairs = usize::from(values.contains(&(TARGET - value)));
+    let triples = values
+        .iter()
+        .filter(|&&u| {
+            let w = TARGET - value - u;
+            u < w && values.contains(&w)
+        })
+        .count();
+
+    (pairs, triples)
+}
+
+impl Generator for Day1 {
+    const DEFAULT_SIZE: usize = 200;
+
+    // Plants a single pair and a single triple summing to `TARGET`, among values which don't make `TARGET` in any
+    // other way.  Half of the time the values include a lone `TARGET / 2`, which mustn't be paired with itself.
+    fn generate(rng: &mut StdRng, size: usize) -> Generated {
+        let (pair, triple, mut values) = loop {
+            let a = rng.gen_range(1..TARGET / 2);
+            let x = rng.gen_range(1..TARGET / 3);
+            let y = rng.gen_range(x + 1..(TARGET - x + 1) / 2);
+            let pair = [a, TARGET - a];
+            let triple = [x, y, TARGET - x - y];
+
+            // Each planted value must only complete its own pair or triple
+            let mut values = HashSet::new();
+            let planted = pair.iter().chain(triple.iter());
+            let expected = [(0, 0), (1, 0), (0, 0), (0, 0), (0, 1)];
+            if planted.zip(expected.iter()).all(|(&value, &expected)| {
+                solutions_with(&values, value) == expected && values.insert(value)
+            }) {
+                break (pair, triple, values);
+            }
+        };
+
+        if rng.gen() && solutions_with(&values, TARGET / 2) == (0, 0) {
+            values.insert(TARGET / 2);
+        }
+
+        // Values above `TARGET` can never be part of a solution, so are a fallback once the smaller values run out
+        let mut attempts = 0;
+        while values.len() < size {
+            if attempts < 100 {
+                let value = rng.gen_range(1..=TARGET);
+                if !values.contains(&value) && solutions_with(&values, value) == (0, 0) {
+                    values.insert(value);
+                    attempts = 0;
+                } else {
+                    attempts += 1;
+                }
+            } else {
+                values.insert(rng.gen_range(TARGET + 1..TARGET + 1 + 10 * size as i64));
+            }
+        }
+
+        let mut values: Vec<i64> = values.into_iter().collect();
+        values.sort_unstable();
+        values.shuffle(rng);
+        let input = values
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Generated::new(input)
+            .part_1(pair[0] * pair[1])
+            .part_2(triple[0] * triple[1] * triple[2])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_example() {
+        let values = [1721, 979, 366, 299, 675, 1456];
+
+        assert_eq!(two_sum_product(&values, TARGET), Some(514579));
+        assert_eq!(three_sum_product(&values, TARGET), Some(241861950));
+
+        let values = [-10_000_000_000, 4_000_000_000_000_000_000, 10_000_002_020];
+        assert_eq!(
+            two_sum_product(&values, TARGET),
+            Some(-100_000_020_200_000_000_000)
+        );
+        let values = [
+            4_000_000_000_000_000_000,
+            4_000_000_000_000_000_000,
+            2020 - 8_000_000_000_000_000_000,
+        ];
+        assert_eq!(three_sum_product(&values, TARGET), None);
+    }
+
+    #[test]
+    fn test_k_sum() {
+        // A lone half of the target isn't a pair, but two of them are
+        assert!(k_sum(&[1010, 5, 2000], 2, 2020).is_empty());
+        assert_eq!(k_sum(&[1010, 5, 1010], 2, 2020), vec![vec![0, 2]]);
+
+        assert_eq!(
+            k_sum(&[-5, 2025, 0, 2020], 2, 2020),
+            vec![vec![0, 1], vec![2, 3]]
+        );
+        assert_eq!(
+            k_sum(&[1, 1, 2, 1], 3, 3),
+            vec![vec![0, 1, 3]],
+            "three equal values are one way of choosing three"
+        );
+        assert_eq!(k_sum(&[2, 2, 2, 2], 3, 6).len(), 4);
+        assert_eq!(
+            k_sum(&[-3, 1, 2, 4, -1, 0], 4, 0),
+            vec![vec![0, 1, 2, 5], vec![0, 3, 4, 5]]
+        );
+
+        assert_eq!(k_sum(&[1, 2], 0, 0), vec![Vec::<usize>::new()]);
+        assert_eq!(k_sum(&[3, 1, 3], 1, 3), vec![vec![0], vec![2]]);
+        assert!(k_sum(&[1, 2], 3, 3).is_empty());
+        assert!(k_sum(&[i64::MAX, i64::MAX, i64::MIN], 2, -2).is_empty());
+    }
+
+    #[test]
+    fn test_first_k_sum() {
+        let values = [1721, 979, 366, 299, 675, 1456];
+
+        assert_eq!(first_k_sum(&values, 2, 2020), Some(vec![0, 3]));
+        assert_eq!(first_k_sum(&values, 3, 2020), Some(vec![1, 2, 4]));
+        assert_eq!(first_k_sum(&values, 4, 2020), None);
+    }
+
+    #[test]
+    fn test_queries() {
+        let values = [1721, 979, 366, 299, 675, 1456];
+
+        assert_eq!(closest_k_sum(&values, 2, 2000), Some((1822, vec![2, 5])));
+        assert_eq!(closest_k_sum(&values, 2, 2020), Some((2020, vec![0, 3])));
+        assert_eq!(closest_k_sum(&values, 3, 2000), Some((1953, vec![1, 3, 4])));
+        assert_eq!(closest_k_sum(&values, 2, 600), None);
+        assert_eq!(closest_k_sum(&[-4, 7, -9], 2, -10), Some((-13, vec![0, 2])));
+
+        assert_eq!(count_k_sum(&[1, 2, 3, 4, 5], 2, 6), 2);
+        assert_eq!(count_k_sum(&[5; 6], 3, 15), 20);
+
+        let found = k_sum_targets(&values, 3, &[2020, 1340, 1, 2020]);
+        assert_eq!(found.keys().copied().collect::<Vec<_>>(), vec![1340, 2020]);
+        assert_eq!(found[&1340], vec![vec![2, 3, 4]]);
+        assert_eq!(found[&2020], vec![vec![1, 2, 4]]);
+    }
+
+    #[test]
+    fn test_scan_target() {
+        let scan = scan_stream("3\n9\n5\n1\n4\n".as_bytes(), 10).unwrap();
+        assert_eq!(scan.two_sum_product, Some(9));
+        assert_eq!(scan.three_sum_product, Some(20));
+
+        let scan = scan_stream("5000000000\n5000000000\n1\n".as_bytes(), 10_000_000_000).unwrap();
+        assert_eq!(scan.two_sum_product, Some(25_000_000_000_000_000_000));
+
+        let third = i64::MAX / 3;
+        let input = format!("{}\n{}\n{}\n", third, third, third);
+        let scan = scan_stream(input.as_bytes(), 3 * third).unwrap();
+        assert_eq!(scan.three_sum_product, None);
+    }
+
+    // Every combination of `k` indices, in increasing order
+    fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+        if k == 0 {
+            return vec![Vec::new()];
+        }
+
+        (k - 1..n)
+            .flat_map(|last| {
+                combinations(last, k - 1).into_iter().map(move |mut c| {
+                    c.push(last);
+                    c
+                })
+            })
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn prop_k_sum(values in prop::collection::vec(-20i64..20, 0..10), k in 0usize..6, target in -40i64..40) {
+            let mut expected: Vec<Vec<usize>> = combinations(values.len(), k)
+                .into_iter()
+                .filter(|c| c.iter().map(|&idx| values[idx]).sum::<i64>() == target)
+                .collect();
+            expected.sort();
+
+            prop_assert_eq!(&k_sum(&values, k, target), &expected);
+            prop_assert_eq!(count_k_sum(&values, k, target), expected.len());
+            match first_k_sum(&values, k, target) {
+                Some(first) => prop_assert!(expected.contains(&first)),
+                None => prop_assert!(expected.is_empty()),
+            }
+        }
+
+        #[test]
+        fn prop_closest_k_sum(values in prop::collection::vec(-20i64..20, 0..10), k in 0usize..5, target in -40i64..40) {
+            let sum = |c: &[usize]| c.iter().map(|&idx| values[idx]).sum::<i64>();
+            let best = combinations(values.len(), k)
+                .iter()
+                .map(|c| sum(c))
+                .filter(|&s| s <= target)
+                .max();
+
+            let closest = closest_k_sum(&values, k, target);
+            prop_assert_eq!(closest.as_ref().map(|(s, _)| *s), best);
+            if let Some((s, indices)) = closest {
+                prop_assert_eq!(indices.len(), k);
+                prop_assert!(indices.windows(2).all(|w| w[0] < w[1]));
+                prop_assert_eq!(sum(&indices), s);
+            }
+        }
+    }
+
+    #[test]
+    fn test_stream() {
+        let (part_1, part_2) =
+            Day1::solve_stream("1721\n979\n366\n299\n675\n1456\n".as_bytes()).unwrap();
+        assert_eq!(part_1, Answer::Number(514579));
+        assert_eq!(part_2, Answer::Number(241861950));
+
+        // Nothing after the answers have been found is read
+        let (part_1, _) = Day1::solve_stream("1721\n299\n979\n366\n675\nx".as_bytes()).unwrap();
+        assert_eq!(part_1, Answer::Number(514579));
+
+        match Day1::solve_stream("5\n-3\n".as_bytes()) {
+            Err(StreamError::Parse(err)) => {
+                assert_eq!(
+                    (err.line, err.expected.as_str()),
+                    (2, "a non-negative number")
+                )
+            }
+            result => panic!("expected a parse error, found {:?}", result),
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::{BinaryHeap, HashMap};
+
+use common::{
+    error::{parse_lines, parse_number},
+    generate::{Generated, Generator, Rng, SliceRandom, StdRng},
+    trace, Answer, ParseError, Solution,
+};
+
+// The second part is None if the number of ways to arrange the adapters overflows, as it soon does for long runs of
+// adapters one jolt apart
+fn chain(adapters: &[i32]) -> (usize, Option<u64>) {
+    let mut jolts: BinaryHeap<i32> = adapters.iter().copied().collect();
+
+    jolts.push(0);
+    let built_in_max = jolts.peek().unwrap() + 3;
+    jolts.push(built_in_max);
+
+    let mut ones = 0;
+    let mut threes = 0;
+
+    let mut num_ways: HashMap<i32, Option<u64>> = HashMap::new();
+    num_ways.insert(built_in_max, Some(1));
+
+    let mut higher_jolt = jolts.pop().unwrap();
+    while let Some(lower_jolt) = jolts.pop() {
+        // Part 1
+        if higher_jolt - lower_jolt == 1 {
+            ones += 1;
+        }
+        if higher_jolt - lower_jolt == 3 {
+            threes += 1;
+        }
+
+        // Part 2
+        let num_ways_from = [lower_jolt + 1, lower_jolt + 2, lower_jolt + 3]
+            .iter()
+            .try_fold(0u64, |sum, jolt| {
+                sum.checked_add(num_ways.get(jolt).copied().unwrap_or(Some(0))?)
+            });
+        trace::step("adapter", |fields| {
+            fields.with("jolts", lower_jolt).with("ways", num_ways_from)
+        });
+        num_ways.insert(lower_jolt, num_ways_from);
+
+        higher_jolt = lower_jolt;
+    }
+
+    trace::summary("chain", |fields| {
+        fields
+            .with("device", built_in_max)
+            .with("ones", ones)
+            .with("threes", threes)
+    });
+
+    (ones * threes, *num_ways.get(&0).unwrap())
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Vec<i32>;
+
+    const DAY: u8 = 10;
+
+    fn parse(input: &str) -> Result<Vec<i32>, ParseError> {
+        let adapters = parse_lines(input, |line| parse_number(line, line))?;
+
+        if adapters.is_empty() {
+            return Err(ParseError::end_of_input(input, "an adapter rating"));
+        }
+
+        Ok(adapters)
+    }
+
+    fn part1(adapters: &Vec<i32>) -> Answer {
+        chain(adapters).0.into()
+    }
+
+    fn part2(adapters: &Vec<i32>) -> Answer {
+        chain(adapters).1.into()
+    }
+}
+
+impl Generator for Day10 {
+    const DEFAULT_SIZE: usize = 100;
+
+    // Adapters one or three jolts apart.  Three jolt gaps must always be taken, so the ways of arranging the adapters
+    // are the product of the ways of crossing each run of one jolt gaps, taking one, two or three at a time.
+    fn generate(rng: &mut StdRng, size: usize) -> Generated {
+        let mut jolt = 0;
+        let mut adapters = Vec::with_capacity(size);
+        let (mut ones, mut threes) = (0, 1);
+        let mut runs = vec![0];
+
+        for _ in 0..size.max(1) {
+            if rng.gen_bool(0.6) {
+                jolt += 1;
+                ones += 1;
+                *runs.last_mut().unwrap() += 1;
+            } else {
+                jolt += 3;
+                threes += 1;
+                runs.push(0);
+            }
+            adapters.push(jolt);
+        }
+        adapters.shuffle(rng);
+
+        let mut ways: Vec<Option<u64>> = vec![Some(1), Some(1), Some(2)];
+        let arrangements = runs.iter().try_fold(1u64, |product, &run| {
+            while ways.len() <= run {
+                let next = ways[ways.len() - 3..]
+                    .iter()
+                    .try_fold(0u64, |sum, ways| sum.checked_add((*ways)?));
+                ways.push(next);
+            }
+            product.checked_mul(ways[run]?)
+        });
+
+        let input = adapters
+            .iter()
+            .map(|adapter| adapter.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let generated = Generated::new(input).part_1(ones * threes);
+
+        match arrangements {
+            Some(arrangements) => generated.part_2(arrangements),
+            None => generated,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let input = "16
+10
+15
+5
+1
+11
+7
+19
+6
+12
+4";
+
+        let adapters = Day10::parse(input).unwrap();
+        assert_eq!(Day10::part1(&adapters), Answer::Number(35));
+        assert_eq!(Day10::part2(&adapters), Answer::Number(8));
+    }
+}
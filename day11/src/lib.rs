@@ -1,4 +1,11 @@
-use std::str::FromStr;
+use std::{collections::HashSet, io};
+
+use common::{
+    automaton::{Automaton, Rule},
+    grid::{Cell, Point, DIRECTIONS_8},
+    visualize::{Flat, Glyphs, Player, Visualize},
+    Answer, Grid, ParseError, Solution,
+};
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Tile {
@@ -7,140 +14,113 @@ pub enum Tile {
     OccupiedSeat,
 }
 
-#[derive(Debug)]
+impl Cell for Tile {
+    const EXPECTED: &'static str = "'L', '#' or '.'";
+    const NAME: &'static str = "seat";
+
+    fn from_char(c: char) -> Option<Tile> {
+        match c {
+            'L' => Some(Tile::EmptySeat),
+            '#' => Some(Tile::OccupiedSeat),
+            '.' => Some(Tile::Floor),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Tile::EmptySeat => 'L',
+            Tile::OccupiedSeat => '#',
+            Tile::Floor => '.',
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
 pub enum Mode {
     Adjacent,
     Directional,
 }
 
-#[derive(Debug)]
-pub struct Automata {
-    tiles: Vec<Vec<Tile>>,
-    mode: Mode,
+// The seats which affect whether each seat is taken: those next to it, or the first seen in each direction, looking
+// across the floor
+fn seen(layout: &Grid<Tile>, mode: Mode) -> Grid<Vec<Point>> {
+    layout.map(|p, _| match mode {
+        Mode::Adjacent => layout
+            .neighbours_8(p)
+            .filter(|&q| layout[q] != Tile::Floor)
+            .collect(),
+        Mode::Directional => DIRECTIONS_8
+            .iter()
+            .filter_map(|&direction| layout.ray(p, direction).find(|&q| layout[q] != Tile::Floor))
+            .collect(),
+    })
 }
 
-impl Automata {
-    pub fn new(tiles: Vec<Vec<Tile>>) -> Automata {
-        let mode = Mode::Adjacent;
-        Automata { tiles, mode }
-    }
+// The seating system as an automaton over the seats, with the occupied seats being active
+pub fn seating(layout: &Grid<Tile>, mode: Mode) -> Automaton<Point, impl Fn(Point) -> Vec<Point>> {
+    let seen = seen(layout, mode);
+    let tolerance = match mode {
+        Mode::Adjacent => 3,
+        Mode::Directional => 4,
+    };
+    let survives: Vec<usize> = (0..=tolerance).collect();
 
-    pub fn set_mode(&mut self, mode: Mode) {
-        self.mode = mode;
-    }
-}
+    let occupied = layout.points().filter(|&p| layout[p] == Tile::OccupiedSeat);
+    let seats = layout.points().filter(|&p| layout[p] != Tile::Floor);
 
-fn part_1(tiles: &Vec<Vec<Tile>>, p: (usize, usize)) -> Tile {
-    let (x, y) = p;
-    let deltas = vec![
-        (1, 0),
-        (1, -1),
-        (0, -1),
-        (-1, -1),
-        (-1, 0),
-        (-1, 1),
-        (0, 1),
-        (1, 1),
-    ];
-
-    let adjacent_occupied = deltas
-        .iter()
-        .map(|(dx, dy)| (x as i64 + dx, y as i64 + dy))
-        .filter(|(x, y)| *x >= 0 && *x < tiles[0].len() as i64 && *y >= 0 && *y < tiles.len() as i64)
-        .map(|(x, y)| tiles[y as usize][x as usize])
-        .filter(|tile| *tile == Tile::OccupiedSeat)
-        .count();
-
-    let tile = tiles[y][x];
-    match (tile, adjacent_occupied) {
-        (Tile::OccupiedSeat, x) if x >= 4 => Tile::EmptySeat,
-        (Tile::EmptySeat, 0) => Tile::OccupiedSeat,
-        _ => tile,
-    }
+    Automaton::new(
+        occupied,
+        move |p| seen[p].clone(),
+        Rule::new(&[0], &survives),
+    )
+    .within(seats)
 }
 
-fn part_2(tiles: &Vec<Vec<Tile>>, p: (usize, usize)) -> Tile {
-    let (x, y) = p;
-    let deltas = vec![
-        (1, 0),
-        (1, -1),
-        (0, -1),
-        (-1, -1),
-        (-1, 0),
-        (-1, 1),
-        (0, 1),
-        (1, 1),
-    ];
-    let mut nearby_occupied = 0;
-    for (dx, dy) in deltas {
-        let mut x = x as i64 + dx;
-        let mut y = y as i64 + dy;
-        while x >= 0 && x < tiles[0].len() as i64 && y >= 0 && y < tiles.len() as i64 {
-            let tile = tiles[y as usize][x as usize];
-            match tile {
-                Tile::OccupiedSeat => {
-                    nearby_occupied += 1;
-                    break;
-                }
-                Tile::EmptySeat => break,
-                Tile::Floor => {
-                    x += dx;
-                    y += dy;
-                }
-            }
-        }
-    }
-
-    let tile = tiles[y][x];
-    match (tile, nearby_occupied) {
-        (Tile::OccupiedSeat, x) if x >= 5 => Tile::EmptySeat,
-        (Tile::EmptySeat, 0) => Tile::OccupiedSeat,
-        _ => tile,
-    }
+pub fn render(layout: &Grid<Tile>, occupied: &HashSet<Point>) -> Grid<Tile> {
+    layout.map(|p, &tile| match tile {
+        Tile::Floor => Tile::Floor,
+        _ if occupied.contains(&p) => Tile::OccupiedSeat,
+        _ => Tile::EmptySeat,
+    })
 }
 
-impl Iterator for Automata {
-    type Item = Vec<Vec<Tile>>;
+pub struct Day11;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let current = self.tiles.clone();
+impl Solution for Day11 {
+    type Input = Grid<Tile>;
 
-        let idxs = (0..self.tiles.len()).map(|y| (0..self.tiles[0].len()).map(move |x| (x, y)));
+    const DAY: u8 = 11;
 
-        let new = idxs
-            .map(|i| {
-                i.map(|(x, y)| match self.mode {
-                    Mode::Adjacent => part_1(&self.tiles, (x, y)),
-                    Mode::Directional => part_2(&self.tiles, (x, y)),
-                })
-                .collect()
-            })
-            .collect();
+    fn parse(input: &str) -> Result<Grid<Tile>, ParseError> {
+        input.parse()
+    }
 
-        self.tiles = new;
+    fn part1(layout: &Grid<Tile>) -> Answer {
+        seating(layout, Mode::Adjacent).run_until_stable().into()
+    }
 
-        Some(current)
+    fn part2(layout: &Grid<Tile>) -> Answer {
+        seating(layout, Mode::Directional).run_until_stable().into()
     }
 }
 
-impl FromStr for Automata {
-    type Err = String;
+impl Visualize for Day11 {
+    // Drawn the same way as the puzzle draws the seats
+    fn visualize(layout: &Grid<Tile>, player: &mut Player) -> io::Result<()> {
+        let glyphs = Glyphs {
+            active: Tile::OccupiedSeat.to_char(),
+            inactive: Tile::EmptySeat.to_char(),
+            outside: Tile::Floor.to_char(),
+        };
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut tiles: Vec<Vec<Tile>> = Vec::new();
-        for line in s.lines() {
-            let mut row: Vec<Tile> = Vec::new();
-            for char in line.trim().chars() {
-                match char {
-                    'L' => row.push(Tile::EmptySeat),
-                    '#' => row.push(Tile::OccupiedSeat),
-                    '.' => row.push(Tile::Floor),
-                    _ => return Err(format!("Invalid character: {}", char)),
-                }
+        for (title, mode) in [("Part 1", Mode::Adjacent), ("Part 2", Mode::Directional)].iter() {
+            if !player.watch(title, &mut seating(layout, *mode), &Flat, glyphs, None)? {
+                break;
             }
-            tiles.push(row);
         }
-        Ok(Automata::new(tiles))
+
+        Ok(())
     }
 }
 
@@ -149,7 +129,7 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_foo() {
+    fn test_step() {
         let f = "L.LL.LL.LL
 LLLLLLL.LL
 L.L.L..L..
@@ -161,9 +141,10 @@ LLLLLLLLLL
 L.LLLLLL.L
 L.LLLLL.LL";
 
-        let mut automata: Automata = f.parse().unwrap();
+        let layout: Grid<Tile> = f.parse().unwrap();
+        let mut seating = seating(&layout, Mode::Adjacent);
 
-        let expected_first = Some(vec![
+        let expected_first = Grid::from_rows(vec![
             vec![
                 Tile::EmptySeat,
                 Tile::Floor,
@@ -286,9 +267,9 @@ L.LLLLL.LL";
             ],
         ]);
 
-        assert_eq!(automata.next(), expected_first);
+        assert_eq!(Some(render(&layout, seating.active())), expected_first);
 
-        let expected_second = Some(vec![
+        let expected_second = Grid::from_rows(vec![
             vec![
                 Tile::OccupiedSeat,
                 Tile::Floor,
@@ -411,6 +392,7 @@ L.LLLLL.LL";
             ],
         ]);
 
-        assert_eq!(automata.next(), expected_second);
+        seating.step();
+        assert_eq!(Some(render(&layout, seating.active())), expected_second);
     }
 }
@@ -1,6 +1,11 @@
 use std::str::FromStr;
 
-#[derive(Copy, Clone)]
+use common::{
+    error::{parse_lines, parse_number},
+    trace, Answer, ParseError, Solution,
+};
+
+#[derive(Debug, Copy, Clone)]
 pub enum Rotation {
     Zero,
     PiOverTwo,
@@ -19,6 +24,7 @@ impl Rotation {
     }
 }
 
+#[derive(Debug)]
 pub enum Action {
     Forward(i64),
     North(i64),
@@ -30,24 +36,16 @@ pub enum Action {
 }
 
 impl FromStr for Action {
-    type Err = String;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut chars = s.chars();
-
-        let f = chars.next();
-
-        if f == None {
-            return Err(String::from("Unable to parse empty string"));
-        }
-
-        let g = chars.collect::<String>().parse::<i64>();
+        let f = match s.chars().next() {
+            Some(f) => f,
+            None => return Err(ParseError::end_of_input(s, "an action")),
+        };
+        let (action, value) = s.split_at(f.len_utf8());
 
-        if let Err(e) = g {
-            return Err(format!("Unable to parse int: {}", e.to_string()));
-        }
-
-        match (f.unwrap(), g.unwrap()) {
+        match (f, parse_number(s, value)?) {
             ('F', d) => Ok(Action::Forward(d)),
             ('N', d) => Ok(Action::North(d)),
             ('E', d) => Ok(Action::East(d)),
@@ -61,7 +59,12 @@ impl FromStr for Action {
             ('R', 90) => Ok(Action::Right(Rotation::PiOverTwo)),
             ('R', 180) => Ok(Action::Right(Rotation::Pi)),
             ('R', 270) => Ok(Action::Right(Rotation::ThreePiOverTwo)),
-            _ => Err(format!("Unable to parse action: {}", s)),
+            ('L', _) | ('R', _) => Err(ParseError::new(s, value, "0, 90, 180 or 270")),
+            _ => Err(ParseError::new(
+                s,
+                action,
+                "one of 'F', 'N', 'E', 'S', 'W', 'L' or 'R'",
+            )),
         }
     }
 }
@@ -124,12 +127,86 @@ impl Vector {
     }
 }
 
+static NORTH: Vector = Vector(0, 1);
+static EAST: Vector = Vector(1, 0);
+static SOUTH: Vector = Vector(0, -1);
+static WEST: Vector = Vector(-1, 0);
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Vec<Action>;
+
+    const DAY: u8 = 12;
+
+    fn parse(input: &str) -> Result<Vec<Action>, ParseError> {
+        parse_lines(input, str::parse)
+    }
+
+    fn part1(actions: &Vec<Action>) -> Answer {
+        let mut ship = Vector(0, 0);
+        let mut direction = Vector(1, 0);
+
+        for action in actions {
+            match *action {
+                Action::Forward(n) => ship += n * direction,
+                Action::North(n) => ship += n * NORTH,
+                Action::East(n) => ship += n * EAST,
+                Action::South(n) => ship += n * SOUTH,
+                Action::West(n) => ship += n * WEST,
+                Action::Left(r) => direction.rotate(r),
+                Action::Right(r) => direction.rotate(r.inverse()),
+            }
+            trace::step("move", |fields| {
+                fields
+                    .with("action", format!("{:?}", action))
+                    .with("ship", vec![ship.0, ship.1])
+                    .with("direction", vec![direction.0, direction.1])
+            });
+        }
+        trace::summary("arrived", |fields| {
+            fields.with("ship", vec![ship.0, ship.1])
+        });
+
+        ship.l1_norm().into()
+    }
+
+    fn part2(actions: &Vec<Action>) -> Answer {
+        let mut ship = Vector(0, 0);
+        let mut waypoint = Vector(10, 1);
+
+        for action in actions {
+            match *action {
+                Action::Forward(n) => ship += n * waypoint,
+                Action::North(n) => waypoint += n * NORTH,
+                Action::East(n) => waypoint += n * EAST,
+                Action::South(n) => waypoint += n * SOUTH,
+                Action::West(n) => waypoint += n * WEST,
+                Action::Left(r) => waypoint.rotate(r),
+                Action::Right(r) => waypoint.rotate(r.inverse()),
+            }
+            trace::step("move", |fields| {
+                fields
+                    .with("action", format!("{:?}", action))
+                    .with("ship", vec![ship.0, ship.1])
+                    .with("waypoint", vec![waypoint.0, waypoint.1])
+            });
+        }
+        trace::summary("arrived", |fields| {
+            fields.with("ship", vec![ship.0, ship.1])
+        });
+
+        ship.l1_norm().into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
-    fn test_foo() {
+    fn test_navigate() {
         let north = Vector(0, 1);
         let mut ship = Vector(0, 0);
         let mut direction = Vector(1, 0);
@@ -142,4 +219,48 @@ mod tests {
 
         assert_eq!(ship.l1_norm(), 25)
     }
+
+    #[test]
+    fn test_example() {
+        let input = "F10
+N3
+F7
+R90
+F11";
+
+        let actions = Day12::parse(input).unwrap();
+        assert_eq!(Day12::part1(&actions), Answer::Number(25));
+        assert_eq!(Day12::part2(&actions), Answer::Number(286));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = Day12::parse("F10\nR45").unwrap_err();
+        assert_eq!((err.line, err.column, err.snippet.as_str()), (2, 2, "45"));
+
+        let err = Day12::parse("F10\nX3").unwrap_err();
+        assert_eq!((err.line, err.column, err.snippet.as_str()), (2, 1, "X"));
+    }
+
+    fn rotation() -> impl Strategy<Value = Rotation> {
+        prop_oneof![
+            Just(Rotation::Zero),
+            Just(Rotation::PiOverTwo),
+            Just(Rotation::Pi),
+            Just(Rotation::ThreePiOverTwo),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn prop_rotate_inverse(x in -1000..1000i64, y in -1000..1000i64, rotation in rotation()) {
+            let mut vector = Vector(x, y);
+
+            vector.rotate(rotation);
+            prop_assert_eq!(vector.l1_norm(), Vector(x, y).l1_norm());
+
+            vector.rotate(rotation.inverse());
+            prop_assert_eq!((vector.0, vector.1), (x, y));
+        }
+    }
 }
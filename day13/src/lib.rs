@@ -1,66 +1,109 @@
-pub fn extended_euclid(x: i128, y: i128) -> (i128, (i128, i128)) {
-    let (mut d, mut d_prev) = (x, y);
-    let (mut a, mut a_prev) = (1, 0);
-    let (mut b, mut b_prev) = (0, 1);
-
-    let mut s: i128;
-    let mut t: i128;
-    while d_prev != 0 {
-        let q = d / d_prev;
-
-        s = d;
-        t = d_prev;
-
-        d = t;
-        d_prev = s - q * t;
+use common::{
+    error::parse_number, numtheory::chinese_remainder, trace, Answer, ParseError, Solution,
+};
+
+#[derive(Debug)]
+pub struct Notes {
+    pub earliest_time: i128,
+    pub ids: Vec<Option<i128>>,
+}
 
-        s = a;
-        t = a_prev;
+pub struct Day13;
 
-        a = t;
-        a_prev = s - q * t;
+impl Solution for Day13 {
+    type Input = Notes;
+    const DAY: u8 = 13;
 
-        s = b;
-        t = b_prev;
+    fn parse(input: &str) -> Result<Notes, ParseError> {
+        let mut lines = input.lines();
 
-        b = t;
-        b_prev = s - q * t;
+        let earliest_time = match lines.next() {
+            Some(line) => parse_number(input, line)?,
+            None => {
+                return Err(ParseError::end_of_input(
+                    input,
+                    "the earliest departure time",
+                ))
+            }
+        };
+
+        let ids = match lines.next() {
+            Some(timetable) => timetable
+                .split(',')
+                .map(|s| match s {
+                    "x" => Ok(None),
+                    // An ID is how often the bus departs, so it has to be positive
+                    _ => match parse_number(input, s)? {
+                        id if id > 0 => Ok(Some(id)),
+                        _ => Err(ParseError::new(input, s, "a positive bus ID or 'x'")),
+                    },
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            None => return Err(ParseError::end_of_input(input, "a list of bus IDs")),
+        };
+
+        Ok(Notes { earliest_time, ids })
     }
 
-    (d, (a, b))
-}
-
-pub fn chinese_remainder(linear_congruences: &[(i128, i128)]) -> Option<i128> {
-    // Solves a system of linear congruences in `x` given the form of pairs of (`a_i`, `n_i`) such that:
-    // `x` === `a_1` (mod `n_1`)
-    // `x` === `a_2` (mod `n_2`)
-    // ...
-    // `x` === `a_k` (mod `n_k`)
-    // (where the input has length k)
-    //
-    // Outputs a solution (if one exists) `a` such that:
-    // `a` is a solution to the given congruences
-    // `a` is unique modulo `n_1` * `n_2` * ... * `n_k`
-    let mut linear_congruences = linear_congruences.iter();
-    if let Some((a_0, n_0)) = linear_congruences.next() {
-        let mut a = *a_0;
-        let mut n = *n_0;
-        for (a_i, n_i) in linear_congruences {
-            let (d_i, (u_i, v_i)) = extended_euclid(n, *n_i);
-            if d_i != 1 {
-                return None;
-            }
-            a = a * v_i * *n_i + *a_i * u_i * n;
-            n *= n_i;
+    fn part1(notes: &Notes) -> Answer {
+        let earliest_time = notes.earliest_time;
+
+        notes
+            .ids
+            .iter()
+            .flatten()
+            .map(|id| {
+                let departs = if earliest_time % id == 0 {
+                    earliest_time
+                } else {
+                    earliest_time + id - (earliest_time % id)
+                };
+                trace::step("bus", |fields| {
+                    fields
+                        .with("id", *id as i64)
+                        .with("departs", departs as i64)
+                });
+                (departs, *id)
+            })
+            .min()
+            .map(|(first_bus_time, first_bus_id)| {
+                trace::summary("first bus", |fields| {
+                    fields
+                        .with("id", first_bus_id as i64)
+                        .with("wait", (first_bus_time - earliest_time) as i64)
+                });
+                first_bus_id * (first_bus_time - earliest_time)
+            })
+            .into()
+    }
 
-            // Normalise x to 0 <= a < n.  The two modulus operations account for both +ive and -ive values of a.
-            a = ((a % n) + n) % n;
+    fn part2(notes: &Notes) -> Answer {
+        let linear_congruences = notes
+            .ids
+            .iter()
+            .enumerate()
+            .filter_map(|(t, id)| id.map(|n| (-(t as i128), n)))
+            .collect::<Vec<_>>();
+
+        for &(a, n) in linear_congruences.iter() {
+            trace::step("congruence", |fields| {
+                fields
+                    .with("residue", a.rem_euclid(n) as i64)
+                    .with("modulus", n as i64)
+            });
         }
 
-        return Some(a);
+        chinese_remainder(&linear_congruences)
+            .map(|(t, n)| {
+                trace::summary("solution", |fields| {
+                    fields
+                        .with("t", t.to_string())
+                        .with("modulus", n.to_string())
+                });
+                t
+            })
+            .into()
     }
-
-    return None;
 }
 
 #[cfg(test)]
@@ -68,20 +111,28 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_euclid() {
-        let (d, (a, b)) = extended_euclid(19, 25);
-        assert_eq!(a * 19 + b * 25, d);
-        assert_eq!(d, 1);
+    fn test_example() {
+        let input = "939
+7,13,x,x,59,x,31,19";
+
+        let notes = Day13::parse(input).unwrap();
+        assert_eq!(Day13::part1(&notes), Answer::Number(295));
+        assert_eq!(Day13::part2(&notes), Answer::Number(1068781));
     }
 
     #[test]
-    fn test_crt() {
-        let x = chinese_remainder(&vec![(0, 3), (3, 4), (4, 5)]).unwrap();
+    fn test_parse_error() {
+        let err = Day13::parse("939\n7,13,y,59").unwrap_err();
+        assert_eq!((err.line, err.column, err.snippet.as_str()), (2, 6, "y"));
+
+        let err = Day13::parse("939\n7,0,x").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.expected, "a positive bus ID or 'x'");
 
-        assert_eq!(0, x % 3);
-        assert_eq!(3, x % 4);
-        assert_eq!(4, x % 5);
+        let err = Day13::parse("939\nx,-13").unwrap_err();
+        assert_eq!((err.line, err.column, err.snippet.as_str()), (2, 3, "-13"));
 
-        assert_eq!(x % 60, x);
+        let err = Day13::parse("939").unwrap_err();
+        assert_eq!((err.line, err.column, err.snippet.as_str()), (1, 4, ""));
     }
 }
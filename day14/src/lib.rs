@@ -1,6 +1,7 @@
-#[macro_use]
-extern crate lazy_static;
-use regex::Regex;
+use common::{
+    parser::{integer, lines, map, or, parse, take_while1, try_map, Parser},
+    trace, Answer, ParseError, Solution,
+};
 use std::{collections::HashMap, str::FromStr};
 
 pub enum Version {
@@ -8,34 +9,36 @@ pub enum Version {
     V2,
 }
 
+#[derive(Debug)]
 pub enum Instruction {
     Mask(Vec<char>),
     SetMemory(usize, u64),
 }
 
+// A line of the form "mask = <36 bits>" or "mem[<address>] = <value>"
+fn instruction<'a>() -> impl Parser<'a, Instruction> {
+    let bits = try_map(
+        take_while1(|c| matches!(c, '0' | '1' | 'X'), "'0', '1' or 'X'"),
+        |bits: &str| match bits.len() {
+            36 => Ok(bits.chars().collect()),
+            _ => Err("a mask of 36 bits".to_string()),
+        },
+    );
+
+    or(
+        map(("mask = ", bits), |(_, bits)| Instruction::Mask(bits)),
+        map(
+            ("mem[", integer(), "] = ", integer()),
+            |(_, address, _, value)| Instruction::SetMemory(address, value),
+        ),
+    )
+}
+
 impl FromStr for Instruction {
-    type Err = String;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        lazy_static! {
-            static ref MASK_RE: Regex = Regex::new(r"mask = ([01X]{36})").unwrap();
-        }
-        lazy_static! {
-            static ref SET_MEMORY_RE: Regex = Regex::new(r"mem\[(\d+)\] = (\d+)").unwrap();
-        }
-
-        if let Some(captures) = MASK_RE.captures(s) {
-            return Ok(Instruction::Mask(captures[1].chars().collect()));
-        }
-
-        if let Some(captures) = SET_MEMORY_RE.captures(s) {
-            return Ok(Instruction::SetMemory(
-                captures[1].parse().unwrap(),
-                captures[2].parse().unwrap(),
-            ));
-        }
-
-        Err(format!("Unable to decode instruction: {}", s))
+        parse(s, instruction())
     }
 }
 
@@ -103,6 +106,9 @@ impl Machine {
         match self.version {
             Version::V1 => {
                 let value = (value & self.and_mask) | self.or_mask;
+                trace::step("write", |fields| {
+                    fields.with("address", address).with("value", value)
+                });
 
                 self.memory.insert(address, value);
             }
@@ -112,6 +118,12 @@ impl Machine {
                 let unfloated_address = address & !floating_mask | or_mask;
 
                 let ones = floating_mask.count_ones() as usize;
+                trace::step("write", |fields| {
+                    fields
+                        .with("address", unfloated_address)
+                        .with("value", value)
+                        .with("addresses", 1u64 << ones)
+                });
                 let mut floating_mask_powers_of_two: Vec<usize> = Vec::with_capacity(ones);
                 for bit_position in 0..36 {
                     let power_of_two = 1 << bit_position;
@@ -121,9 +133,11 @@ impl Machine {
                 }
                 for floating_mask_idx in 0..(1 << ones) {
                     let mut floating_mask = 0;
-                    for power_of_two_idx in 0..ones {
+                    for (power_of_two_idx, power_of_two) in
+                        floating_mask_powers_of_two.iter().enumerate()
+                    {
                         if floating_mask_idx & (1 << power_of_two_idx) > 0 {
-                            floating_mask |= floating_mask_powers_of_two[power_of_two_idx];
+                            floating_mask |= power_of_two;
                         }
                     }
 
@@ -145,12 +159,45 @@ impl Machine {
                 Instruction::SetMemory(addr, val) => self.set_memory(*addr, *val),
             }
         }
+        trace::summary("memory", |fields| {
+            fields
+                .with("addresses", self.memory.len())
+                .with("sum", self.check())
+        });
 
         Ok(())
     }
 
     pub fn check(&self) -> u64 {
-        self.memory.values().map(|x| *x).sum()
+        self.memory.values().sum()
+    }
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Program;
+
+    const DAY: u8 = 14;
+
+    fn parse(input: &str) -> Result<Program, ParseError> {
+        parse(input, lines(instruction()))
+    }
+
+    fn part1(program: &Program) -> Answer {
+        let mut machine = Machine::new(Version::V1);
+
+        let _ = machine.run(program);
+
+        machine.check().into()
+    }
+
+    fn part2(program: &Program) -> Answer {
+        let mut machine = Machine::new(Version::V2);
+
+        let _ = machine.run(program);
+
+        machine.check().into()
     }
 }
 
@@ -189,4 +236,18 @@ mod tests {
 
         assert_eq!(machine.check(), 208)
     }
+
+    #[test]
+    fn test_parse_error() {
+        let err = Day14::parse("mask = 1X0\nmem[8] = 11").unwrap_err();
+        assert_eq!((err.line, err.column, err.snippet.as_str()), (1, 8, "1X0"));
+        assert_eq!(err.expected, "a mask of 36 bits");
+
+        let err = Day14::parse("mem[8] = 11\nmem[x] = 11").unwrap_err();
+        assert_eq!((err.line, err.column, err.snippet.as_str()), (2, 5, "x"));
+
+        let err = Day14::parse("mem[8] = 11\nmask 0").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.expected, "\"mask = \" or \"mem[\"");
+    }
 }
@@ -1,5 +1,10 @@
 use std::collections::VecDeque;
 
+use common::{error::parse_number, trace, Answer, ParseError, Solution};
+
+// Every number spoken in the puzzle's 30 million turns is below this, which is as many as the game keeps track of
+pub const NUMBERS: usize = 1 << 25;
+
 pub struct MemoryGame {
     turn: usize,
     seen: Vec<Option<usize>>,
@@ -11,12 +16,12 @@ pub struct MemoryGame {
 impl MemoryGame {
     pub fn new(starting_numbers: &[usize]) -> MemoryGame {
         let turn = 0;
-        let seen = vec![None; 1 << 25];
+        let seen = vec![None; NUMBERS];
         let last_spoken = None;
         let next_spoken = None;
         let starting_numbers = starting_numbers
             .iter()
-            .map(|s| *s)
+            .copied()
             .collect::<VecDeque<usize>>();
         MemoryGame {
             turn,
@@ -56,13 +61,55 @@ impl Iterator for MemoryGame {
 
         self.turn += 1;
 
-        return self.last_spoken;
+        self.last_spoken
+    }
+}
+
+// The number spoken on the given turn.  There are far too many turns to trace each of them.
+fn spoken(starting_numbers: &[usize], turn: usize) -> Option<usize> {
+    let spoken = MemoryGame::new(starting_numbers).nth(turn - 1);
+    trace::summary("spoken", |fields| {
+        fields.with("turn", turn).with("number", spoken)
+    });
+
+    spoken
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Vec<usize>;
+
+    const DAY: u8 = 15;
+
+    // The table of when each number was last spoken
+    const MEMORY: usize = NUMBERS * std::mem::size_of::<Option<usize>>();
+
+    fn parse(input: &str) -> Result<Vec<usize>, ParseError> {
+        input
+            .trim()
+            .split(',')
+            .map(|s| match parse_number(input, s)? {
+                n if n < NUMBERS => Ok(n),
+                _ => Err(ParseError::new(input, s, "a number below 33554432")),
+            })
+            .collect()
+    }
+
+    fn part1(starting_numbers: &Vec<usize>) -> Answer {
+        spoken(starting_numbers, 2020).into()
+    }
+
+    fn part2(starting_numbers: &Vec<usize>) -> Answer {
+        spoken(starting_numbers, 30000000).into()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::MemoryGame;
+    use common::Solution;
+
+    use crate::{Day15, MemoryGame};
 
     #[test]
     fn test_memory_game() {
@@ -80,6 +127,15 @@ mod tests {
         assert_eq!(memory_game.next(), Some(0));
     }
 
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(Day15::parse("0,3,33554431\n").unwrap(), [0, 3, 33554431]);
+
+        let err = Day15::parse("0,33554432,6\n").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 3));
+        assert_eq!(err.expected, "a number below 33554432");
+    }
+
     #[test]
     fn test_memory_game_2020() {
         let mut memory_game = MemoryGame::new(&[1, 3, 2]);
@@ -4,12 +4,19 @@ use std::{
     str::FromStr,
 };
 
+use common::{
+    parser::{integer, lines, map, parse, separated, take_while1, try_map, Parser},
+    trace::{self, Level},
+    Answer, ParseError, Solution,
+};
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum Status {
     Valid(HashSet<String>),
     Invalid(u64),
 }
 
+#[derive(Debug)]
 pub struct FieldRanges {
     ranges: HashMap<String, Vec<RangeInclusive<u64>>>,
 }
@@ -19,6 +26,14 @@ impl FieldRanges {
         FieldRanges { ranges }
     }
 
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
     pub fn validate(&self, ticket: &Ticket) -> Vec<Status> {
         let mut validity = Vec::new();
 
@@ -42,33 +57,34 @@ impl FieldRanges {
     }
 }
 
-impl FromStr for FieldRanges {
-    type Err = ();
+// Lines of the form "<field name>: A-B or C-D"
+fn field_ranges<'a>() -> impl Parser<'a, FieldRanges> {
+    let range = map((integer(), "-", integer()), |(a, _, b)| a..=b);
+    let field = (
+        take_while1(|c| c != ':' && c != '\n', "a field name"),
+        ": ",
+        separated(range, " or "),
+    );
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut ranges = HashMap::new();
-        for line in s.lines() {
-            let mut line_iter = line.split(": ").into_iter();
-            let field = line_iter.next().expect("Expected line of the form <field name>: A-B or C-D");
-            let field_ranges = line_iter
-                .next()
-                .expect("Expected line of the form <field name>: A-B or C-D")
-                .split(" or ")
+    map(lines(field), |fields| {
+        FieldRanges::new(
+            fields
                 .into_iter()
-                .map(|s| {
-                    let mut range_iter = s.split('-').into_iter();
-                    let a = range_iter.next().expect("Expected range of the form A-B").parse().expect("Expected integer");
-                    let b = range_iter.next().expect("Expected range of the form A-B").parse().expect("Expected integer");
-                    a..=b
-                })
-                .collect();
-            ranges.insert(field.to_string(), field_ranges);
-        }
+                .map(|(field, _, ranges)| (field.to_string(), ranges))
+                .collect(),
+        )
+    })
+}
+
+impl FromStr for FieldRanges {
+    type Err = ParseError;
 
-        Ok(FieldRanges { ranges })
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s, field_ranges())
     }
 }
 
+#[derive(Debug)]
 pub struct Ticket {
     fields: Vec<u64>,
 }
@@ -78,21 +94,189 @@ impl Ticket {
         let fields = Vec::from(fields);
         Ticket { fields }
     }
-    pub fn fields(&self) -> &Vec<u64> {
+    pub fn fields(&self) -> &[u64] {
         &self.fields
     }
 }
 
+// A comma separated list of numbers
+fn ticket<'a>() -> impl Parser<'a, Ticket> {
+    map(separated(integer(), ","), |fields| Ticket { fields })
+}
+
+// A ticket with a value for each of the given number of fields
+fn ticket_of<'a>(fields: usize) -> impl Parser<'a, Ticket> {
+    try_map(ticket(), move |ticket| {
+        if ticket.fields().len() == fields {
+            Ok(ticket)
+        } else {
+            Err(format!(
+                "a ticket with a value for each of the {} fields",
+                fields
+            ))
+        }
+    })
+}
+
 impl FromStr for Ticket {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let fields = s
-            .split(",")
-            .map(|t| t.parse().expect("Unable to parse int"))
-            .collect();
+        parse(s, ticket())
+    }
+}
+
+#[derive(Debug)]
+pub struct Notes {
+    pub field_ranges: FieldRanges,
+    pub my_ticket: Ticket,
+    pub other_tickets: Vec<Ticket>,
+}
+
+type CandidateFields = Vec<(usize, HashSet<String>)>;
+
+fn scan(notes: &Notes) -> (u64, Option<CandidateFields>) {
+    let mut part_1_sum = 0;
+    let mut part_2_candidate_fields: Option<CandidateFields> = None;
+
+    for (ticket_idx, other_ticket) in notes.other_tickets.iter().enumerate() {
+        let mut invalid = false;
+        let mut other_ticket_candidate_fields: CandidateFields = Vec::new();
+        for (idx, validity) in notes.field_ranges.validate(other_ticket).iter().enumerate() {
+            match validity {
+                Status::Valid(candidate_fields) => {
+                    other_ticket_candidate_fields.push((idx, candidate_fields.clone()))
+                }
+                Status::Invalid(field_error) => {
+                    trace::step("invalid", |fields| {
+                        fields
+                            .with("ticket", ticket_idx)
+                            .with("value", *field_error)
+                    });
+                    invalid = true;
+                    part_1_sum += field_error
+                }
+            }
+        }
+
+        match (&part_2_candidate_fields, invalid) {
+            (None, false) => part_2_candidate_fields = Some(other_ticket_candidate_fields),
+            (Some(candidate_fields), false) => {
+                part_2_candidate_fields = Some(
+                    candidate_fields
+                        .iter()
+                        .zip(other_ticket_candidate_fields.iter())
+                        .map(|((idx, accumulated_intersection), (_, candidate_fields))| {
+                            (
+                                *idx,
+                                accumulated_intersection
+                                    .intersection(candidate_fields)
+                                    .cloned()
+                                    .collect(),
+                            )
+                        })
+                        .collect(),
+                )
+            }
+            _ => continue,
+        }
+    }
+
+    (part_1_sum, part_2_candidate_fields)
+}
+
+pub fn assign_fields(notes: &Notes) -> Option<HashMap<usize, String>> {
+    let (_, mut part_2_candidate_fields) = scan(notes);
+
+    if let Some(ref mut part_2_candidate_fields) = part_2_candidate_fields {
+        part_2_candidate_fields.sort_by_key(|(_, candidate_fields)| candidate_fields.len());
+
+        let mut fields = HashMap::new();
+        let mut seen_fields = HashSet::new();
+
+        for (idx, candidate_fields) in part_2_candidate_fields.iter() {
+            // The column can only be told apart from the others if exactly one of its fields is still left
+            let mut remaining = candidate_fields.difference(&seen_fields);
+            let next_field = match (remaining.next(), remaining.next()) {
+                (Some(field), None) => field,
+                _ => return None,
+            };
+            trace::step("assign", |fields| {
+                fields
+                    .with("column", *idx)
+                    .with("field", next_field.as_str())
+                    .with("candidates", candidate_fields.len())
+            });
+            fields.insert(*idx, next_field.clone());
+            seen_fields.extend(candidate_fields.clone());
+        }
+
+        return Some(fields);
+    }
+
+    None
+}
+
+pub struct Day16;
 
-        Ok(Ticket { fields })
+impl Solution for Day16 {
+    type Input = Notes;
+    const DAY: u8 = 16;
+
+    fn parse(input: &str) -> Result<Notes, ParseError> {
+        // Every ticket needs a value for each of the fields listed before them
+        let notes = |rest| {
+            let (field_ranges, rest) = field_ranges().parse(rest)?;
+            let fields = field_ranges.len();
+            let tickets = (
+                "\n\nyour ticket:\n",
+                ticket_of(fields),
+                "\n\nnearby tickets:\n",
+                lines(ticket_of(fields)),
+            );
+
+            let ((_, my_ticket, _, other_tickets), rest) = tickets.parse(rest)?;
+            let notes = Notes {
+                field_ranges,
+                my_ticket,
+                other_tickets,
+            };
+
+            Ok((notes, rest))
+        };
+
+        parse(input, notes)
+    }
+
+    fn part1(notes: &Notes) -> Answer {
+        let error_rate = scan(notes).0;
+        trace::summary("error rate", |fields| fields.with("sum", error_rate));
+
+        error_rate.into()
+    }
+
+    fn part2(notes: &Notes) -> Answer {
+        let fields = assign_fields(notes);
+        if trace::enabled(Level::Summary) {
+            let mut columns: Vec<_> = fields.iter().flatten().collect();
+            columns.sort();
+            for (column, field) in columns {
+                trace::summary("assigned", |fields| {
+                    fields.with("column", *column).with("field", field.as_str())
+                });
+            }
+        }
+
+        fields
+            .map(|fields| {
+                let my_ticket_fields = notes.my_ticket.fields();
+                fields
+                    .iter()
+                    .filter(|(_, field)| field.starts_with("departure"))
+                    .map(|(idx, _)| my_ticket_fields[*idx])
+                    .product::<u64>()
+            })
+            .into()
     }
 }
 
@@ -165,4 +349,119 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_part_1() {
+        let input = "class: 1-3 or 5-7
+row: 6-11 or 33-44
+seat: 13-40 or 45-50
+
+your ticket:
+7,1,14
+
+nearby tickets:
+7,3,47
+40,4,50
+55,2,20
+38,6,12";
+
+        let notes = Day16::parse(input).unwrap();
+        assert_eq!(Day16::part1(&notes), Answer::Number(71));
+    }
+
+    #[test]
+    fn test_assign_fields() {
+        let input = "class: 0-1 or 4-19
+row: 0-5 or 8-19
+seat: 0-13 or 16-19
+
+your ticket:
+11,12,13
+
+nearby tickets:
+3,9,18
+15,1,5
+5,14,9";
+
+        let fields = assign_fields(&Day16::parse(input).unwrap()).unwrap();
+
+        assert_eq!(fields.get(&0), Some(&"row".to_string()));
+        assert_eq!(fields.get(&1), Some(&"class".to_string()));
+        assert_eq!(fields.get(&2), Some(&"seat".to_string()));
+
+        let input = "class: 0-19
+row: 0-19
+
+your ticket:
+11,12
+
+nearby tickets:
+3,9";
+        assert_eq!(assign_fields(&Day16::parse(input).unwrap()), None);
+
+        let input = "class: 0-19
+row: 0-19
+seat: 0-5
+
+your ticket:
+11,12,3
+
+nearby tickets:
+9,3,3";
+        assert_eq!(assign_fields(&Day16::parse(input).unwrap()), None);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let input = "class: 0-1 or 4-19
+row: 0-5 or 8-x
+
+your ticket:
+11,12,13
+
+nearby tickets:
+3,9,18";
+        let err = Day16::parse(input).unwrap_err();
+        assert_eq!((err.line, err.column, err.snippet.as_str()), (2, 15, "x"));
+
+        let input = "class: 0-1 or 4-19
+row: 0-5 or 8-19
+seat: 0-13 or 16-19
+
+your ticket:
+11,12,13
+
+nearby tickets:
+3,9,18
+15,,5";
+        let err = Day16::parse(input).unwrap_err();
+        assert_eq!((err.line, err.column, err.snippet.as_str()), (10, 4, ","));
+
+        let input = "class: 0-1 or 4-19
+row: 0-5 or 8-19
+
+your ticket:
+11,12
+
+nearby tickets:
+3,9
+15,1,5";
+        let err = Day16::parse(input).unwrap_err();
+        assert_eq!((err.line, err.column), (9, 1));
+        assert_eq!(
+            err.expected,
+            "a ticket with a value for each of the 2 fields"
+        );
+
+        let input = "class: 0-1 or 4-19
+row: 0-5 or 8-19
+
+your ticket:
+11
+
+nearby tickets:
+3,9";
+        let err = Day16::parse(input).unwrap_err();
+        assert_eq!((err.line, err.column, err.snippet.as_str()), (5, 1, "11"));
+    }
 }
@@ -0,0 +1,120 @@
+use std::io;
+
+use common::{
+    automaton::{moore, Automaton, Moore, Rule},
+    grid::Cell,
+    visualize::{Glyphs, Player, Slices, Visualize},
+    Answer, Grid, ParseError, Solution,
+};
+
+const CYCLES: usize = 6;
+
+type PocketDimension<const D: usize> = Automaton<[i64; D], fn([i64; D]) -> Moore<D>>;
+
+// The boot process in `D` dimensions, with the initial slice lying flat in the first two
+fn boot<const D: usize>(active_cubes: &[(i64, i64)]) -> PocketDimension<D> {
+    let cubes = active_cubes.iter().map(|&(x, y)| {
+        let mut p = [0; D];
+        p[0] = x;
+        p[1] = y;
+        p
+    });
+
+    Automaton::new(cubes, moore as fn(_) -> _, Rule::new(&[3], &[2, 3]))
+}
+
+fn simulate<const D: usize>(active_cubes: &[(i64, i64)]) -> usize {
+    boot::<D>(active_cubes).run(CYCLES)
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Cube {
+    Active,
+    Inactive,
+}
+
+impl Cell for Cube {
+    const EXPECTED: &'static str = "'#' or '.'";
+    const NAME: &'static str = "cube";
+
+    fn from_char(c: char) -> Option<Cube> {
+        match c {
+            '#' => Some(Cube::Active),
+            '.' => Some(Cube::Inactive),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Cube::Active => '#',
+            Cube::Inactive => '.',
+        }
+    }
+}
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = Vec<(i64, i64)>;
+    const DAY: u8 = 17;
+
+    fn parse(input: &str) -> Result<Vec<(i64, i64)>, ParseError> {
+        let grid: Grid<Cube> = input.parse()?;
+
+        Ok(grid
+            .points()
+            .filter(|&p| grid[p] == Cube::Active)
+            .map(|(x, y)| (x as i64, y as i64))
+            .collect())
+    }
+
+    fn part1(active_cubes: &Vec<(i64, i64)>) -> Answer {
+        simulate::<3>(active_cubes).into()
+    }
+
+    fn part2(active_cubes: &Vec<(i64, i64)>) -> Answer {
+        simulate::<4>(active_cubes).into()
+    }
+}
+
+impl Visualize for Day17 {
+    // A slice of the pocket dimension for each z, and in four dimensions for each w too
+    fn visualize(active_cubes: &Vec<(i64, i64)>, player: &mut Player) -> io::Result<()> {
+        let glyphs = Glyphs::default();
+
+        if player.watch(
+            "Part 1",
+            &mut boot::<3>(active_cubes),
+            &Slices,
+            glyphs,
+            Some(CYCLES),
+        )? {
+            player.watch(
+                "Part 2",
+                &mut boot::<4>(active_cubes),
+                &Slices,
+                glyphs,
+                Some(CYCLES),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let input = ".#.
+..#
+###";
+
+        let active_cubes = Day17::parse(input).unwrap();
+        assert_eq!(Day17::part1(&active_cubes), Answer::Number(112));
+        assert_eq!(Day17::part2(&active_cubes), Answer::Number(848));
+    }
+}
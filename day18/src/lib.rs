@@ -1,3 +1,8 @@
+use common::{
+    error::{parse_lines, parse_number},
+    trace, Answer, ParseError, Solution,
+};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Binop {
     A,
@@ -21,7 +26,8 @@ pub enum Expr {
 }
 
 impl Expr {
-    pub fn new(tokens: &[Token]) -> Result<Box<Expr>, ()> {
+    // Builds an expression from a token stream, or returns None if the tokens are not a well-formed expression
+    pub fn new(tokens: &[Token]) -> Option<Box<Expr>> {
         let mut expr = None;
 
         let mut i = 0;
@@ -35,7 +41,7 @@ impl Expr {
                     let mut j = i + 1;
                     let mut lparens = 0;
                     loop {
-                        match (&tokens[j], lparens) {
+                        match (tokens.get(j)?, lparens) {
                             (Token::L, _) => lparens += 1,
                             (Token::R, 0) => break,
                             (Token::R, _) => lparens -= 1,
@@ -48,7 +54,7 @@ impl Expr {
                 }
                 (Some(acc_expr), Token::B(binop)) => {
                     let rhs: Box<Expr>;
-                    match &tokens[i + 1] {
+                    match tokens.get(i + 1)? {
                         Token::V(w) => {
                             rhs = Box::new(Expr::V(*w));
                             i += 2;
@@ -57,7 +63,7 @@ impl Expr {
                             let mut j = i + 2;
                             let mut lparens = 0;
                             loop {
-                                match (&tokens[j], lparens) {
+                                match (tokens.get(j)?, lparens) {
                                     (Token::L, _) => lparens += 1,
                                     (Token::R, 0) => break,
                                     (Token::R, _) => lparens -= 1,
@@ -68,49 +74,94 @@ impl Expr {
                             rhs = Expr::new(&tokens[i + 2..j])?;
                             i = j + 1;
                         }
-                        _ => return Err(()),
+                        _ => return None,
                     }
                     match binop {
                         Binop::A => expr = Some(Box::new(Expr::A(acc_expr, rhs))),
                         Binop::M => expr = Some(Box::new(Expr::M(acc_expr, rhs))),
                     }
                 }
-                _ => return Err(()),
+                _ => return None,
             }
         }
 
-        Ok(expr.unwrap())
+        expr
     }
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Mode {
     InOrder,
     AdditionTakesPrecedence,
 }
 
-pub fn read(line: &str, mode: Mode) -> Vec<Token> {
+pub fn read(line: &str, mode: Mode) -> Result<Vec<Token>, ParseError> {
     let mut tokens: Vec<Token> = Vec::new();
 
-    let preprocessed = line.replace("(", "( ").replace(")", " )");
-    let mut raw_tokens = preprocessed.split_ascii_whitespace();
-
     let mut additions = 0;
 
-    loop {
-        match raw_tokens.next() {
-            None => break,
-            Some("+") => {
+    // Checks the expression is well-formed while reading it, so that the rewriting below and building the expression
+    // afterwards can't fail
+    let mut depth = 0;
+    let mut expecting_operand = true;
+    let mut chars = line.char_indices().peekable();
+    while let Some((idx, c)) = chars.next() {
+        match (c, expecting_operand) {
+            (' ', _) => continue,
+            ('(', true) => {
+                tokens.push(Token::L);
+                depth += 1;
+            }
+            (')', false) if depth > 0 => {
+                tokens.push(Token::R);
+                depth -= 1;
+            }
+            ('+', false) => {
                 tokens.push(Token::B(Binop::A));
-                additions += 1
+                additions += 1;
+                expecting_operand = true;
+            }
+            ('*', false) => {
+                tokens.push(Token::B(Binop::M));
+                expecting_operand = true;
+            }
+            ('0'..='9', true) => {
+                let mut end = idx + 1;
+                while let Some(&(next_idx, '0'..='9')) = chars.peek() {
+                    end = next_idx + 1;
+                    chars.next();
+                }
+                tokens.push(Token::V(parse_number(line, &line[idx..end])?));
+                expecting_operand = false;
+            }
+            (_, true) => {
+                return Err(ParseError::new(
+                    line,
+                    &line[idx..idx + c.len_utf8()],
+                    "a number or '('",
+                ))
+            }
+            (_, false) => {
+                return Err(ParseError::new(
+                    line,
+                    &line[idx..idx + c.len_utf8()],
+                    if depth > 0 {
+                        "'+', '*' or ')'"
+                    } else {
+                        "'+' or '*'"
+                    },
+                ))
             }
-            Some("*") => tokens.push(Token::B(Binop::M)),
-            Some("(") => tokens.push(Token::L),
-            Some(")") => tokens.push(Token::R),
-            Some(v) => tokens.push(Token::V(v.parse().expect("Expected int"))),
         }
     }
 
+    if expecting_operand {
+        return Err(ParseError::end_of_input(line, "a number or '('"));
+    }
+    if depth > 0 {
+        return Err(ParseError::end_of_input(line, "')'"));
+    }
+
     if mode == Mode::AdditionTakesPrecedence {
         for current_addition in 0..additions {
             let mut addition = 0;
@@ -155,18 +206,73 @@ pub fn read(line: &str, mode: Mode) -> Vec<Token> {
         }
     }
 
-    tokens
+    Ok(tokens)
 }
 
-pub fn eval(expr: Box<Expr>) -> i64 {
-    match *expr {
-        Expr::V(x) => x,
+pub fn eval(expr: &Expr) -> i64 {
+    match expr {
+        Expr::V(x) => *x,
         Expr::A(lhs, rhs) => eval(lhs) + eval(rhs),
         Expr::M(lhs, rhs) => eval(lhs) * eval(rhs),
         Expr::E(expr) => eval(expr),
     }
 }
 
+// Each line of homework parsed under both sets of precedence rules
+#[derive(Debug)]
+pub struct Homework {
+    pub in_order: Vec<Expr>,
+    pub addition_takes_precedence: Vec<Expr>,
+}
+
+fn parse_expressions(input: &str, mode: Mode) -> Result<Vec<Expr>, ParseError> {
+    parse_lines(input, |line| {
+        Expr::new(&read(line, mode)?)
+            .map(|expr| *expr)
+            .ok_or_else(|| ParseError::new(line, line, "a well-formed expression"))
+    })
+}
+
+// The sum of the values of every line of homework
+fn sum(expressions: &[Expr]) -> i64 {
+    let mut sum = 0;
+    for (idx, expr) in expressions.iter().enumerate() {
+        let value = eval(expr);
+        trace::step("evaluated", |fields| {
+            fields.with("line", idx + 1).with("value", value)
+        });
+        sum += value;
+    }
+    trace::summary("sum", |fields| {
+        fields.with("lines", expressions.len()).with("sum", sum)
+    });
+
+    sum
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = Homework;
+
+    const DAY: u8 = 18;
+
+    fn parse(input: &str) -> Result<Homework, ParseError> {
+        Ok(Homework {
+            in_order: parse_expressions(input, Mode::InOrder)?,
+            addition_takes_precedence: parse_expressions(input, Mode::AdditionTakesPrecedence)?,
+        })
+    }
+
+    fn part1(homework: &Homework) -> Answer {
+        sum(&homework.in_order).into()
+    }
+
+    fn part2(homework: &Homework) -> Answer {
+        sum(&homework.addition_takes_precedence).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -182,37 +288,49 @@ mod tests {
             )))),
         ));
 
-        assert_eq!(eval(expr), 26)
+        assert_eq!(eval(&expr), 26)
     }
 
     #[test]
     fn test_read_eval() {
-        assert_eq!(eval(Expr::new(&read("1", Mode::InOrder)).unwrap()), 1);
-        assert_eq!(eval(Expr::new(&read("1 + 2", Mode::InOrder)).unwrap()), 3);
         assert_eq!(
-            eval(Expr::new(&read("1 + 2 * 3 + 4 * 5 + 6", Mode::InOrder)).unwrap()),
+            eval(&Expr::new(&read("1", Mode::InOrder).unwrap()).unwrap()),
+            1
+        );
+        assert_eq!(
+            eval(&Expr::new(&read("1 + 2", Mode::InOrder).unwrap()).unwrap()),
+            3
+        );
+        assert_eq!(
+            eval(&Expr::new(&read("1 + 2 * 3 + 4 * 5 + 6", Mode::InOrder).unwrap()).unwrap()),
             71
         );
         assert_eq!(
-            eval(Expr::new(&read("1 + (2 * 3) + (4 * (5 + 6))", Mode::InOrder)).unwrap()),
+            eval(&Expr::new(&read("1 + (2 * 3) + (4 * (5 + 6))", Mode::InOrder).unwrap()).unwrap()),
             51
         );
         assert_eq!(
             eval(
-                Expr::new(&read(
-                    "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2",
-                    Mode::InOrder
-                ))
+                &Expr::new(
+                    &read(
+                        "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2",
+                        Mode::InOrder
+                    )
+                    .unwrap()
+                )
                 .unwrap()
             ),
             13632
         );
         assert_eq!(
             eval(
-                Expr::new(&read(
-                    "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2",
-                    Mode::AdditionTakesPrecedence
-                ))
+                &Expr::new(
+                    &read(
+                        "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2",
+                        Mode::AdditionTakesPrecedence
+                    )
+                    .unwrap()
+                )
                 .unwrap()
             ),
             23340
@@ -222,11 +340,11 @@ mod tests {
     #[test]
     fn test_read_addition_takes_precedence() {
         assert_eq!(
-            read("1 * 2", Mode::AdditionTakesPrecedence),
+            read("1 * 2", Mode::AdditionTakesPrecedence).unwrap(),
             vec![Token::V(1), Token::B(Binop::M), Token::V(2)]
         );
         assert_eq!(
-            read("1 + 2", Mode::AdditionTakesPrecedence),
+            read("1 + 2", Mode::AdditionTakesPrecedence).unwrap(),
             vec![
                 Token::L,
                 Token::V(1),
@@ -236,7 +354,7 @@ mod tests {
             ]
         );
         assert_eq!(
-            read("1 + 2 * 3 + 4 * 5 + 6", Mode::AdditionTakesPrecedence),
+            read("1 + 2 * 3 + 4 * 5 + 6", Mode::AdditionTakesPrecedence).unwrap(),
             vec![
                 Token::L,
                 Token::V(1),
@@ -258,7 +376,7 @@ mod tests {
             ]
         );
         assert_eq!(
-            read("1 + (2 * 3) + (4 * (5 + 6))", Mode::AdditionTakesPrecedence),
+            read("1 + (2 * 3) + (4 * (5 + 6))", Mode::AdditionTakesPrecedence).unwrap(),
             vec![
                 Token::L,
                 Token::L,
@@ -286,4 +404,19 @@ mod tests {
             ]
         )
     }
+
+    #[test]
+    fn test_parse_error() {
+        let err = Day18::parse("1 + 2\n3 * (4 + x)\n5").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 10));
+        assert_eq!(err.expected, "a number or '('");
+
+        let err = Day18::parse("1 + (2 * 3").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 11));
+        assert_eq!(err.expected, "')'");
+
+        let err = Day18::parse("1 2").unwrap_err();
+        assert_eq!(err.snippet, "2");
+        assert_eq!(err.expected, "'+' or '*'");
+    }
 }
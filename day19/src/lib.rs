@@ -1,4 +1,9 @@
-use std::{collections::HashMap, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+
+use common::{error::parse_number, trace, Answer, ParseError, Solution};
 
 #[derive(Debug, Clone)]
 enum Pattern {
@@ -7,6 +12,7 @@ enum Pattern {
     A(Vec<usize>),
 }
 
+#[derive(Debug)]
 pub struct Matcher {
     patterns: HashMap<usize, Pattern>,
 }
@@ -102,72 +108,217 @@ impl Matcher {
     }
 }
 
+// Parses a space separated sequence of rule numbers, noting each one so that it can be checked once all the rules
+// have been read
+fn parse_sequence<'a>(
+    line: &str,
+    sequence: &'a str,
+    references: &mut Vec<(usize, &'a str)>,
+) -> Result<Vec<usize>, ParseError> {
+    let mut idxs = vec![];
+    for token in sequence.split_ascii_whitespace() {
+        let idx = parse_number(line, token)?;
+        references.push((idx, token));
+        idxs.push(idx);
+    }
+
+    if idxs.is_empty() {
+        return Err(ParseError::new(line, sequence, "a rule number"));
+    }
+
+    Ok(idxs)
+}
+
+fn parse_rule<'a>(
+    line: &'a str,
+    references: &mut Vec<(usize, &'a str)>,
+) -> Result<(usize, Pattern), ParseError> {
+    let (idx, pattern) = line
+        .split_once(": ")
+        .ok_or_else(|| ParseError::new(line, line, "a rule of the form \"<number>: <pattern>\""))?;
+    let idx = parse_number(line, idx.trim())?;
+    let pattern = pattern.trim();
+
+    let pattern = match pattern.split_once(" | ") {
+        Some((lhs, rhs)) => Pattern::O(
+            parse_sequence(line, lhs, references)?,
+            parse_sequence(line, rhs, references)?,
+        ),
+        None => {
+            let mut cs = pattern.chars();
+            match [cs.next(), cs.next(), cs.next(), cs.next()] {
+                [Some('"'), Some(c), Some('"'), None] => Pattern::C(vec![c]),
+                _ => Pattern::A(parse_sequence(line, pattern, references)?),
+            }
+        }
+    };
+
+    Ok((idx, pattern))
+}
+
+// A rule which can lead back to itself without matching any input in between, which would have matching go round
+// forever, if there is one
+fn left_recursive(patterns: &HashMap<usize, Pattern>) -> Option<usize> {
+    // The first rule of each of a rule's sequences is tried at the same point in the input as the rule itself
+    let mut remaining: HashMap<usize, Vec<usize>> = patterns
+        .iter()
+        .map(|(idx, pattern)| {
+            let leads = match pattern {
+                Pattern::C(_) => vec![],
+                Pattern::O(lhs, rhs) => lhs
+                    .iter()
+                    .take(1)
+                    .chain(rhs.iter().take(1))
+                    .copied()
+                    .collect(),
+                Pattern::A(idxs) => idxs.iter().take(1).copied().collect(),
+            };
+            (*idx, leads)
+        })
+        .collect();
+
+    // Rules which only lead to rules which can't loop can't loop either, and whatever's left leads into a loop
+    loop {
+        let settled: Vec<usize> = remaining
+            .iter()
+            .filter(|(_, leads)| leads.iter().all(|lead| !remaining.contains_key(lead)))
+            .map(|(idx, _)| *idx)
+            .collect();
+        if settled.is_empty() {
+            break;
+        }
+        for idx in settled {
+            remaining.remove(&idx);
+        }
+    }
+
+    let mut idx = *remaining.keys().min()?;
+    let mut seen = HashSet::new();
+    while seen.insert(idx) {
+        idx = *remaining[&idx]
+            .iter()
+            .find(|lead| remaining.contains_key(lead))?;
+    }
+
+    Some(idx)
+}
+
 impl FromStr for Matcher {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lines = s.trim().lines();
-
         let mut patterns = HashMap::new();
+        let mut references = vec![];
+        let mut lines = HashMap::new();
 
-        for line in lines {
-            let line = line.trim();
-
-            let mut idx_pattern_iter = line.split(": ");
-
-            let idx = idx_pattern_iter
-                .next()
-                .unwrap()
-                .trim()
-                .parse::<usize>()
-                .unwrap();
-
-            let pattern = idx_pattern_iter.next().unwrap().trim();
-
-            let mut pattern_iter = pattern.split(" | ");
-            match (pattern_iter.next(), pattern_iter.next()) {
-                (Some(lhs), Some(rhs)) => {
-                    let lhs_idxs: Vec<_> = lhs
-                        .split_ascii_whitespace()
-                        .map(|c| c.parse::<usize>().unwrap())
-                        .collect();
-                    let rhs_idxs: Vec<_> = rhs
-                        .split_ascii_whitespace()
-                        .map(|c| c.parse::<usize>().unwrap())
-                        .collect();
-
-                    patterns.insert(idx, Pattern::O(lhs_idxs, rhs_idxs));
-                }
-                (Some(pattern), None) => {
-                    let mut cs = pattern.chars();
-                    match [cs.next(), cs.next(), cs.next(), cs.next()] {
-                        [Some('"'), Some(c), Some('"'), None] => {
-                            patterns.insert(idx, Pattern::C(vec![c]));
-                        }
-                        _ => {
-                            let idxs: Vec<_> = pattern
-                                .split_ascii_whitespace()
-                                .map(|c| c.parse::<usize>().unwrap())
-                                .collect();
-                            patterns.insert(idx, Pattern::A(idxs));
-                        }
-                    }
-                }
-                _ => return Err(()),
-            }
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (idx, pattern) =
+                parse_rule(line, &mut references).map_err(|err| err.within(s, line))?;
+            patterns.insert(idx, pattern);
+            lines.insert(idx, line);
+        }
+
+        // Matching starts from rule 0, and follows every reference, so they must all exist
+        if let Some((_, token)) = references
+            .iter()
+            .find(|(idx, _)| !patterns.contains_key(idx))
+        {
+            return Err(ParseError::new(s, token, "the number of a defined rule"));
+        }
+        if !patterns.contains_key(&0) {
+            return Err(ParseError::end_of_input(s, "a rule numbered 0"));
+        }
+        if let Some(idx) = left_recursive(&patterns) {
+            return Err(ParseError::new(
+                s,
+                lines[&idx],
+                "a rule which matches some input before referring back to itself",
+            ));
         }
 
         Ok(Matcher { patterns })
     }
 }
 
+#[derive(Debug)]
+pub struct Messages {
+    pub matcher: Matcher,
+    // The same rules with 8 and 11 replaced by their looping versions
+    pub looping_matcher: Matcher,
+    pub candidates: Vec<String>,
+}
+
+impl Messages {
+    pub fn count_matches(&self, looping: bool) -> usize {
+        let matcher = if looping {
+            &self.looping_matcher
+        } else {
+            &self.matcher
+        };
+
+        let mut count = 0;
+        for message in self.candidates.iter() {
+            if matcher.matches(message) {
+                trace::step("match", |fields| fields.with("message", message.as_str()));
+                count += 1;
+            }
+        }
+        trace::summary("matches", |fields| {
+            fields
+                .with("count", count)
+                .with("of", self.candidates.len())
+        });
+
+        count
+    }
+}
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = Messages;
+
+    const DAY: u8 = 19;
+
+    fn parse(input: &str) -> Result<Messages, ParseError> {
+        let (rules, messages) = input.split_once("\n\n").ok_or_else(|| {
+            ParseError::end_of_input(input, "a blank line followed by the messages")
+        })?;
+
+        let matcher = rules
+            .parse::<Matcher>()
+            .map_err(|err| err.within(input, rules))?;
+        let looping_matcher = rules
+            .replace("8: 42", "8: 42 | 42 8")
+            .replace("11: 42 31", "11: 42 31 | 42 11 31")
+            .parse::<Matcher>()
+            .map_err(|err| err.within(input, rules))?;
+
+        let candidates = messages.lines().map(|line| line.to_string()).collect();
+
+        Ok(Messages {
+            matcher,
+            looping_matcher,
+            candidates,
+        })
+    }
+
+    fn part1(messages: &Messages) -> Answer {
+        messages.count_matches(false).into()
+    }
+
+    fn part2(messages: &Messages) -> Answer {
+        messages.count_matches(true).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_matcher() {
-        let patterns = vec![
+        let patterns = [
             Pattern::A(vec![4, 1, 5]),
             Pattern::O(vec![2, 3], vec![3, 2]),
             Pattern::O(vec![4, 4], vec![5, 5]),
@@ -184,17 +335,17 @@ mod tests {
 
         let matcher = Matcher { patterns };
 
-        assert_eq!(matcher.matches("ababbb"), true);
-        assert_eq!(matcher.matches("abbbab"), true);
+        assert!(matcher.matches("ababbb"));
+        assert!(matcher.matches("abbbab"));
 
-        assert_eq!(matcher.matches("bababa"), false);
-        assert_eq!(matcher.matches("aaabbb"), false);
-        assert_eq!(matcher.matches("aaaabbb"), false);
+        assert!(!matcher.matches("bababa"));
+        assert!(!matcher.matches("aaabbb"));
+        assert!(!matcher.matches("aaaabbb"));
     }
 
     #[test]
     fn test_matcher_with_loop() {
-        let patterns = vec![
+        let patterns = [
             Pattern::O(vec![2], vec![1, 0]),
             Pattern::C(vec!['a']),
             Pattern::C(vec!['b']),
@@ -208,10 +359,10 @@ mod tests {
 
         let matcher = Matcher { patterns };
 
-        assert_eq!(matcher.matches("b"), true);
-        assert_eq!(matcher.matches("ab"), true);
-        assert_eq!(matcher.matches("aab"), true);
-        assert_eq!(matcher.matches("aaab"), true);
+        assert!(matcher.matches("b"));
+        assert!(matcher.matches("ab"));
+        assert!(matcher.matches("aab"));
+        assert!(matcher.matches("aaab"));
     }
 
     #[test]
@@ -250,9 +401,30 @@ mod tests {
             .parse::<Matcher>()
             .unwrap();
 
+        assert!(matcher.matches("aaabbbbbbaaaabaababaabababbabaaabbababababaaa"));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = Day19::parse("0: 1 2\n1: \"a\"\n2: 1 x\n\nab").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 6));
+        assert_eq!(err.expected, "a number");
+
+        let err = Day19::parse("0: 1 3\n1: \"a\"\n\nab").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 6));
+        assert_eq!(err.expected, "the number of a defined rule");
+
+        let err = Day19::parse("0: 1 2\n1: \"a\"\n2: \"b\"").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 7));
+
+        let err = Day19::parse("0: 1\n1: 0\n\nab").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 1));
         assert_eq!(
-            matcher.matches("aaabbbbbbaaaabaababaabababbabaaabbababababaaa"),
-            true
+            err.expected,
+            "a rule which matches some input before referring back to itself"
         );
+
+        let err = Day19::parse("0: 1 | 2\n1: \"a\"\n2: 3 1\n3: 1 | 2 1\n\nab").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 1));
     }
 }
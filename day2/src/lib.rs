@@ -0,0 +1,677 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    str::FromStr,
+};
+
+use common::{
+    parser::{integer, lines, map, parse, rest_of_line, try_map, word, Parser},
+    trace, Answer, ParseError, Solution,
+};
+use serde_json::{json, Value};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordEntry {
+    pub first: usize,
+    pub second: usize,
+    pub target_char: char,
+    pub password: String,
+}
+
+// A line of the form "1-3 a: abcde"
+fn password_entry<'a>() -> impl Parser<'a, PasswordEntry> {
+    let target_char = try_map(word(), |target: &str| {
+        let mut target_chars = target.chars();
+        match (target_chars.next(), target_chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err("a single letter".to_string()),
+        }
+    });
+
+    map(
+        (
+            integer(),
+            "-",
+            integer(),
+            " ",
+            target_char,
+            ": ",
+            rest_of_line("a password"),
+        ),
+        |(first, _, second, _, target_char, _, password)| PasswordEntry {
+            first,
+            second,
+            target_char,
+            password: password.to_string(),
+        },
+    )
+}
+
+impl FromStr for PasswordEntry {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s, password_entry())
+    }
+}
+
+// Why a password was rejected by a policy
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    CountOutOfRange {
+        target_char: char,
+        count: usize,
+        min: usize,
+        max: usize,
+    },
+    PositionMismatch {
+        target_char: char,
+        matches: usize,
+    },
+    MissingClasses(Vec<CharClass>),
+    ForbiddenSubstring(String),
+    LowEntropy {
+        bits: f64,
+        minimum: f64,
+    },
+}
+
+impl Violation {
+    // The sort of failure, for tallying reasons whose details differ
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Violation::CountOutOfRange { .. } => "count out of range",
+            Violation::PositionMismatch { .. } => "position mismatch",
+            Violation::MissingClasses(_) => "missing classes",
+            Violation::ForbiddenSubstring(_) => "forbidden substring",
+            Violation::LowEntropy { .. } => "low entropy",
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::CountOutOfRange {
+                target_char,
+                count,
+                min,
+                max,
+            } => write!(
+                f,
+                "'{}' appears {} times, expected {} to {}",
+                target_char, count, min, max
+            ),
+            Violation::PositionMismatch {
+                target_char,
+                matches,
+            } => write!(
+                f,
+                "'{}' is at {} of the two positions, expected exactly one",
+                target_char, matches
+            ),
+            Violation::MissingClasses(classes) => {
+                let names: Vec<String> = classes.iter().map(|class| class.to_string()).collect();
+                write!(f, "missing {}", names.join(", "))
+            }
+            Violation::ForbiddenSubstring(substring) => write!(f, "contains \"{}\"", substring),
+            Violation::LowEntropy { bits, minimum } => {
+                write!(f, "entropy of {:.1} bits is below {:.1}", bits, minimum)
+            }
+        }
+    }
+}
+
+// A rule that a password has to satisfy, which may also depend on the rest of its entry
+pub trait PasswordPolicy {
+    // How the policy is written on the command line, which also identifies it in reports
+    fn name(&self) -> String;
+
+    fn check(&self, entry: &PasswordEntry) -> Result<(), Violation>;
+
+    fn is_valid(&self, entry: &PasswordEntry) -> bool {
+        self.check(entry).is_ok()
+    }
+}
+
+// The target character has to appear between `first` and `second` times
+pub struct CountRange;
+
+impl PasswordPolicy for CountRange {
+    fn name(&self) -> String {
+        "count".to_string()
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Result<(), Violation> {
+        let count = entry
+            .password
+            .chars()
+            .filter(|&char| char == entry.target_char)
+            .count();
+
+        if entry.first <= count && count <= entry.second {
+            Ok(())
+        } else {
+            Err(Violation::CountOutOfRange {
+                target_char: entry.target_char,
+                count,
+                min: entry.first,
+                max: entry.second,
+            })
+        }
+    }
+}
+
+// The target character has to be at exactly one of the (1-based) positions `first` and `second`
+pub struct PositionXor;
+
+impl PasswordPolicy for PositionXor {
+    fn name(&self) -> String {
+        "position".to_string()
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Result<(), Violation> {
+        let matches = entry
+            .password
+            .chars()
+            .enumerate()
+            .filter(|&(idx, char)| {
+                (idx + 1 == entry.first || idx + 1 == entry.second) && char == entry.target_char
+            })
+            .count();
+
+        if matches == 1 {
+            Ok(())
+        } else {
+            Err(Violation::PositionMismatch {
+                target_char: entry.target_char,
+                matches,
+            })
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CharClass {
+    Lowercase,
+    Uppercase,
+    Digit,
+    Symbol,
+}
+
+impl CharClass {
+    fn contains(self, char: char) -> bool {
+        match self {
+            CharClass::Lowercase => char.is_lowercase(),
+            CharClass::Uppercase => char.is_uppercase(),
+            CharClass::Digit => char.is_numeric(),
+            CharClass::Symbol => !char.is_alphanumeric() && !char.is_whitespace(),
+        }
+    }
+}
+
+impl fmt::Display for CharClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CharClass::Lowercase => write!(f, "lowercase"),
+            CharClass::Uppercase => write!(f, "uppercase"),
+            CharClass::Digit => write!(f, "digit"),
+            CharClass::Symbol => write!(f, "symbol"),
+        }
+    }
+}
+
+impl FromStr for CharClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lowercase" => Ok(CharClass::Lowercase),
+            "uppercase" => Ok(CharClass::Uppercase),
+            "digit" => Ok(CharClass::Digit),
+            "symbol" => Ok(CharClass::Symbol),
+            _ => Err(format!(
+                "Unknown character class {:?}, expected lowercase, uppercase, digit or symbol",
+                s
+            )),
+        }
+    }
+}
+
+// Every one of the classes has to have at least one character in the password
+pub struct RequiredClasses(pub Vec<CharClass>);
+
+impl PasswordPolicy for RequiredClasses {
+    fn name(&self) -> String {
+        let classes: Vec<String> = self.0.iter().map(|class| class.to_string()).collect();
+        format!("classes:{}", classes.join(","))
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Result<(), Violation> {
+        let missing: Vec<CharClass> = self
+            .0
+            .iter()
+            .copied()
+            .filter(|&class| !entry.password.chars().any(|char| class.contains(char)))
+            .collect();
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(Violation::MissingClasses(missing))
+        }
+    }
+}
+
+// None of the substrings may appear in the password, ignoring case
+pub struct ForbiddenSubstrings(pub Vec<String>);
+
+impl PasswordPolicy for ForbiddenSubstrings {
+    fn name(&self) -> String {
+        format!("forbidden:{}", self.0.join(","))
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Result<(), Violation> {
+        let password = entry.password.to_lowercase();
+        match self
+            .0
+            .iter()
+            .find(|substring| password.contains(&substring.to_lowercase()))
+        {
+            Some(substring) => Err(Violation::ForbiddenSubstring(substring.clone())),
+            None => Ok(()),
+        }
+    }
+}
+
+// The password has to carry at least this many bits of Shannon entropy, going by how often each of its characters
+// appears in it
+pub struct MinimumEntropy(pub f64);
+
+// The total entropy of the password in bits: its length times the entropy per character
+pub fn entropy(password: &str) -> f64 {
+    let mut counts = HashMap::new();
+    for char in password.chars() {
+        *counts.entry(char).or_insert(0usize) += 1;
+    }
+
+    let length = password.chars().count() as f64;
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / length;
+            p * (1.0 / p).log2() * length
+        })
+        .sum()
+}
+
+impl PasswordPolicy for MinimumEntropy {
+    fn name(&self) -> String {
+        format!("entropy:{}", self.0)
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Result<(), Violation> {
+        let bits = entropy(&entry.password);
+        if bits >= self.0 {
+            Ok(())
+        } else {
+            Err(Violation::LowEntropy {
+                bits,
+                minimum: self.0,
+            })
+        }
+    }
+}
+
+// The number of entries whose passwords satisfy `policy`
+pub fn count_valid(entries: &[PasswordEntry], policy: &dyn PasswordPolicy) -> usize {
+    let mut valid = 0;
+    for (idx, entry) in entries.iter().enumerate() {
+        match policy.check(entry) {
+            Ok(()) => valid += 1,
+            Err(violation) => trace::step("invalid", |fields| {
+                fields
+                    .with("line", idx + 1)
+                    .with("password", entry.password.as_str())
+                    .with("reason", violation.to_string())
+            }),
+        }
+    }
+    trace::summary("valid", |fields| {
+        fields.with("count", valid).with("of", entries.len())
+    });
+
+    valid
+}
+
+// A policy as written on the command line: `count`, `position`, `classes:lowercase,digit`, `forbidden:abc,123` or
+// `entropy:20`
+pub fn policy(spec: &str) -> Result<Box<dyn PasswordPolicy>, String> {
+    let (name, argument) = match spec.find(':') {
+        Some(idx) => (&spec[..idx], Some(&spec[idx + 1..])),
+        None => (spec, None),
+    };
+
+    match (name, argument) {
+        ("count", None) => Ok(Box::new(CountRange)),
+        ("position", None) => Ok(Box::new(PositionXor)),
+        ("classes", Some(classes)) => Ok(Box::new(RequiredClasses(
+            classes
+                .split(',')
+                .map(str::parse)
+                .collect::<Result<_, _>>()?,
+        ))),
+        ("forbidden", Some(substrings)) => Ok(Box::new(ForbiddenSubstrings(
+            substrings.split(',').map(str::to_string).collect(),
+        ))),
+        ("entropy", Some(bits)) => bits
+            .parse()
+            .map(|bits| Box::new(MinimumEntropy(bits)) as Box<dyn PasswordPolicy>)
+            .map_err(|_| format!("Expected a number of bits, found {:?}", bits)),
+        _ => Err(format!(
+            "Unknown policy {:?}, expected count, position, classes:LIST, forbidden:LIST or entropy:BITS",
+            spec
+        )),
+    }
+}
+
+// How one entry fared against one policy
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditResult {
+    pub line: usize,
+    pub policy: String,
+    pub violation: Option<Violation>,
+}
+
+// Every well-formed entry checked against every policy, along with the lines which couldn't be parsed as entries
+#[derive(Debug, Clone, PartialEq)]
+pub struct Audit {
+    pub lines: usize,
+    pub malformed: Vec<ParseError>,
+    pub results: Vec<AuditResult>,
+}
+
+pub fn audit(input: &str, policies: &[Box<dyn PasswordPolicy>]) -> Audit {
+    let mut malformed = Vec::new();
+    let mut results = Vec::new();
+    for (idx, line) in input.lines().enumerate() {
+        let entry = match line.parse::<PasswordEntry>() {
+            Ok(entry) => entry,
+            Err(err) => {
+                malformed.push(err.within(input, line));
+                continue;
+            }
+        };
+
+        for policy in policies {
+            results.push(AuditResult {
+                line: idx + 1,
+                policy: policy.name(),
+                violation: policy.check(&entry).err(),
+            });
+        }
+    }
+    trace::summary("audit", |fields| {
+        fields
+            .with("lines", input.lines().count())
+            .with("malformed", malformed.len())
+            .with("policies", policies.len())
+    });
+
+    Audit {
+        lines: input.lines().count(),
+        malformed,
+        results,
+    }
+}
+
+// Quotes a CSV field if it has anything in it which would otherwise be misread
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+impl Audit {
+    // The number of entries passing and failing each policy, in the order the policies were given
+    pub fn totals(&self) -> Vec<(&str, usize, usize)> {
+        let mut totals: Vec<(&str, usize, usize)> = Vec::new();
+        for result in &self.results {
+            let idx = match totals
+                .iter()
+                .position(|(policy, _, _)| *policy == result.policy)
+            {
+                Some(idx) => idx,
+                None => {
+                    totals.push((&result.policy, 0, 0));
+                    totals.len() - 1
+                }
+            };
+            match result.violation {
+                None => totals[idx].1 += 1,
+                Some(_) => totals[idx].2 += 1,
+            }
+        }
+
+        totals
+    }
+
+    // How many times each kind of violation was found
+    pub fn reasons(&self) -> BTreeMap<&'static str, usize> {
+        let mut reasons = BTreeMap::new();
+        for violation in self
+            .results
+            .iter()
+            .filter_map(|result| result.violation.as_ref())
+        {
+            *reasons.entry(violation.kind()).or_insert(0) += 1;
+        }
+
+        reasons
+    }
+
+    // A row per line and policy, plus one for each malformed line, in line order
+    pub fn to_csv(&self) -> String {
+        let mut rows: Vec<(usize, String)> = self
+            .results
+            .iter()
+            .map(|result| {
+                let (outcome, reason) = match &result.violation {
+                    None => ("pass", String::new()),
+                    Some(violation) => ("fail", violation.to_string()),
+                };
+                let row = format!(
+                    "{},{},{},{}",
+                    result.line,
+                    csv_field(&result.policy),
+                    outcome,
+                    csv_field(&reason)
+                );
+                (result.line, row)
+            })
+            .chain(self.malformed.iter().map(|err| {
+                let row = format!("{},,malformed,{}", err.line, csv_field(&err.to_string()));
+                (err.line, row)
+            }))
+            .collect();
+        rows.sort_by_key(|(line, _)| *line);
+
+        let mut csv = "line,policy,result,reason\n".to_string();
+        for (_, row) in rows {
+            csv.push_str(&row);
+            csv.push('\n');
+        }
+
+        csv
+    }
+
+    // The results along with the totals per policy and per kind of violation
+    pub fn to_json(&self) -> String {
+        let malformed: Vec<Value> = self
+            .malformed
+            .iter()
+            .map(|err| json!({"line": err.line, "reason": err.to_string()}))
+            .collect();
+        let policies: Vec<Value> = self
+            .totals()
+            .into_iter()
+            .map(|(policy, passed, failed)| {
+                json!({"policy": policy, "passed": passed, "failed": failed})
+            })
+            .collect();
+        let results: Vec<Value> = self
+            .results
+            .iter()
+            .map(|result| {
+                json!({
+                    "line": result.line,
+                    "policy": result.policy,
+                    "passed": result.violation.is_none(),
+                    "reason": result.violation.as_ref().map(|violation| violation.to_string()),
+                })
+            })
+            .collect();
+
+        json!({
+            "lines": self.lines,
+            "malformed": malformed,
+            "policies": policies,
+            "reasons": self.reasons(),
+            "results": results,
+        })
+        .to_string()
+    }
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<PasswordEntry>;
+
+    const DAY: u8 = 2;
+
+    fn parse(input: &str) -> Result<Vec<PasswordEntry>, ParseError> {
+        parse(input, lines(password_entry()))
+    }
+
+    fn part1(entries: &Vec<PasswordEntry>) -> Answer {
+        count_valid(entries, &CountRange).into()
+    }
+
+    fn part2(entries: &Vec<PasswordEntry>) -> Answer {
+        count_valid(entries, &PositionXor).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let input = "1-3 a: abcde
+1-3 b: cdefg
+2-9 c: ccccccccc";
+
+        let entries = Day2::parse(input).unwrap();
+        assert_eq!(Day2::part1(&entries), Answer::Number(2));
+        assert_eq!(Day2::part2(&entries), Answer::Number(1));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = Day2::parse("1-3 a: abcde\n1-x b: cdefg").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.snippet, "x");
+
+        let err = Day2::parse("1-3 ab: abcde").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 5));
+    }
+
+    #[test]
+    fn test_policies() {
+        let entries = Day2::parse("1-3 b: cdefg\n2-9 c: ccccccccc\n1-2 x: Pa55word!").unwrap();
+
+        assert_eq!(
+            CountRange.check(&entries[0]),
+            Err(Violation::CountOutOfRange {
+                target_char: 'b',
+                count: 0,
+                min: 1,
+                max: 3
+            })
+        );
+        assert_eq!(
+            PositionXor.check(&entries[1]).unwrap_err().to_string(),
+            "'c' is at 2 of the two positions, expected exactly one"
+        );
+
+        let classes = RequiredClasses(vec![
+            CharClass::Lowercase,
+            CharClass::Uppercase,
+            CharClass::Digit,
+            CharClass::Symbol,
+        ]);
+        assert!(classes.is_valid(&entries[2]));
+        assert_eq!(
+            classes.check(&entries[0]).unwrap_err().to_string(),
+            "missing uppercase, digit, symbol"
+        );
+
+        let forbidden = ForbiddenSubstrings(vec!["WORD".to_string()]);
+        assert_eq!(
+            forbidden.check(&entries[2]),
+            Err(Violation::ForbiddenSubstring("WORD".to_string()))
+        );
+        assert!(forbidden.is_valid(&entries[0]));
+
+        assert_eq!(entropy("ccccccccc"), 0.0);
+        assert!((entropy("cdefg") - 5.0 * 5f64.log2()).abs() < 1e-9);
+        assert!(MinimumEntropy(10.0).is_valid(&entries[0]));
+        assert_eq!(
+            MinimumEntropy(10.0)
+                .check(&entries[1])
+                .unwrap_err()
+                .to_string(),
+            "entropy of 0.0 bits is below 10.0"
+        );
+    }
+
+    #[test]
+    fn test_audit() {
+        let input = "1-3 a: abcde\n1-x b: cdefg\n2-8 c: ccccccccc";
+        let policies = vec![policy("count").unwrap(), policy("forbidden:CC,x").unwrap()];
+        let report = audit(input, &policies);
+
+        assert_eq!(report.lines, 3);
+        assert_eq!(report.malformed.len(), 1);
+        assert_eq!(report.malformed[0].line, 2);
+        assert_eq!(
+            report.totals(),
+            vec![("count", 1, 1), ("forbidden:CC,x", 1, 1)]
+        );
+        assert_eq!(
+            report.reasons().into_iter().collect::<Vec<_>>(),
+            vec![("count out of range", 1), ("forbidden substring", 1)]
+        );
+        assert_eq!(
+            report.to_csv(),
+            "line,policy,result,reason
+1,count,pass,
+1,\"forbidden:CC,x\",pass,
+2,,malformed,\"line 2, column 3: expected a number, found \"\"x\"\"\"
+3,count,fail,\"'c' appears 9 times, expected 2 to 8\"
+3,\"forbidden:CC,x\",fail,\"contains \"\"CC\"\"\"
+"
+        );
+        assert!(report
+            .to_json()
+            .starts_with(r#"{"lines":3,"malformed":[{"line":2,"#));
+
+        assert!(policy("classes:digit,emoji").is_err());
+        assert!(policy("entropy").is_err());
+        assert_eq!(policy("entropy:2.5").unwrap().name(), "entropy:2.5");
+    }
+}
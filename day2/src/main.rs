@@ -1,56 +1,82 @@
-use std::io::{self, Read};
-
-use regex::Regex;
-
-fn main() -> io::Result<()> {
-    let re = Regex::new(r"(\d+)-(\d+) ([a-zA-Z]): (.+)").unwrap();
-
-    let mut buffer = String::new();
-    io::stdin().read_to_string(&mut buffer)?;
-
-    let part_1 = &buffer
-        .lines()
-        .filter(|line| match re.captures(line) {
-            Some(captures) => {
-                let min_matching_chars: usize = (&captures[1]).parse().unwrap();
-                let max_matching_chars: usize = (&captures[2]).parse().unwrap();
-                let target_char = &captures[3].chars().next().unwrap();
-                let password = &captures[4];
-
-                let matching_chars = password.chars().filter(|char| char == target_char).count();
-
-                min_matching_chars <= matching_chars && matching_chars <= max_matching_chars
-            }
-            None => false,
-        })
-        .count();
-
-    let part_2 = &buffer
-        .lines()
-        .filter(|line| match re.captures(line) {
-            Some(captures) => {
-                let first_position: usize = (&captures[1]).parse().unwrap();
-                let second_position: usize = (&captures[2]).parse().unwrap();
-                let target_char = &captures[3].chars().next().unwrap();
-                let password = &captures[4];
-
-                let matching_chars = password
-                    .chars()
-                    .enumerate()
-                    .filter(|(idx, char)| {
-                        (idx + 1 == first_position || idx + 1 == second_position)
-                            && char == target_char
-                    })
-                    .count();
-
-                matching_chars == 1
-            }
-            None => false,
-        })
-        .count();
-
-    println!("{}", part_1);
-    println!("{}", part_2);
-
-    Ok(())
+use std::{env, path::PathBuf, process};
+
+use common::{input::InputSource, Solution};
+use day2::{audit, policy, Day2, PasswordPolicy};
+
+static USAGE: &str = "Usage: day2 audit [--policy SPEC]... [--format csv|json] [PATH|-]
+
+Policies are count, position, classes:LIST (of lowercase, uppercase, digit and symbol), forbidden:LIST or
+entropy:BITS, and default to count and position";
+
+enum Format {
+    Csv,
+    Json,
+}
+
+struct AuditArgs {
+    policies: Vec<Box<dyn PasswordPolicy>>,
+    format: Format,
+    path: Option<PathBuf>,
+}
+
+fn parse_audit_args(args: &[String]) -> Result<AuditArgs, String> {
+    let mut policies = Vec::new();
+    let mut format = Format::Csv;
+    let mut path = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--policy" => match args.next() {
+                Some(spec) => policies.push(policy(spec)?),
+                None => return Err("Expected a policy after --policy".to_string()),
+            },
+            "--format" => match args.next().map(String::as_str) {
+                Some("csv") => format = Format::Csv,
+                Some("json") => format = Format::Json,
+                _ => return Err("Expected --format csv or --format json".to_string()),
+            },
+            _ if path.is_none() => path = Some(PathBuf::from(arg)),
+            _ => return Err(format!("Unexpected argument: {}", arg)),
+        }
+    }
+
+    if policies.is_empty() {
+        policies = vec![policy("count")?, policy("position")?];
+    }
+
+    Ok(AuditArgs {
+        policies,
+        format,
+        path,
+    })
+}
+
+fn main() {
+    let source = InputSource::new(Day2::DAY).embedded(include_str!("../input"));
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().map(String::as_str) != Some("audit") {
+        common::solution::main::<Day2>(source);
+        return;
+    }
+
+    let args = parse_audit_args(&args[1..]).unwrap_or_else(|err| {
+        eprintln!("{}\n\n{}", err, USAGE);
+        process::exit(2);
+    });
+    let input = source
+        .path(args.path)
+        .piped_stdin()
+        .read()
+        .unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            process::exit(1);
+        });
+
+    let report = audit(&input, &args.policies);
+    match args.format {
+        Format::Csv => print!("{}", report.to_csv()),
+        Format::Json => println!("{}", report.to_json()),
+    }
 }
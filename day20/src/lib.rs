@@ -1,8 +1,20 @@
-use std::str::FromStr;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    str::FromStr,
+};
+
+use common::{
+    error::parse_number,
+    generate::{Generated, Generator, StdRng},
+    trace, Answer, ParseError, Solution,
+};
+use rand::seq::SliceRandom;
+use rand::Rng;
 
 pub trait D4: Sized + Copy + Clone {
     fn symmetries(&self) -> [Self; 8] {
-        let i = self.clone();
+        let i = *self;
         [
             i,
             i.mirror(1),
@@ -43,9 +55,9 @@ impl D4 for Tile10 {
     }
 
     fn mirror(&self, n: usize) -> Tile10 {
-        let mut rows: [u16; 10] = self.1.clone();
+        let mut rows: [u16; 10] = self.1;
         for _ in 0..(n % 2) {
-            let current_rows = rows.clone();
+            let current_rows = rows;
 
             for (i, row) in current_rows.iter().rev().enumerate() {
                 rows[i] = *row;
@@ -91,34 +103,63 @@ impl Tile10 {
 }
 
 impl FromStr for Tile10 {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut rows: [u16; 10] = [0; 10];
         let mut lines = s.lines();
-        if let Some(header) = lines.next() {
-            if let Some(id) = header.split(|x| x == ' ' || x == ':').skip(1).next() {
-                if let Ok(id) = id.parse() {
-                    let x = lines.map(|l| {
-                        l.trim().chars().fold(Ok(0), |a, c| match (a, c) {
-                            (Ok(a), '.') => Ok(a << 1),
-                            (Ok(a), '#') => Ok((a << 1) + 1),
-                            _ => Err(()),
-                        })
-                    });
-                    for (i, b) in x.enumerate() {
-                        rows[i] = b?
-                    }
 
-                    return Ok(Tile10(id, rows));
-                }
+        let header = lines.next().unwrap_or("").trim_end();
+        let id = header
+            .strip_prefix("Tile ")
+            .and_then(|rest| rest.strip_suffix(':'))
+            .ok_or_else(|| ParseError::new(s, header, "a header of the form \"Tile <id>:\""))?;
+        let id = parse_number(s, id)?;
+
+        let mut rows: [u16; 10] = [0; 10];
+        let mut count = 0;
+        for line in lines.map(str::trim) {
+            let row = rows
+                .get_mut(count)
+                .ok_or_else(|| ParseError::new(s, line, "the end of the tile after 10 rows"))?;
+
+            for (idx, c) in line.char_indices() {
+                *row = match c {
+                    '.' => *row << 1,
+                    '#' => (*row << 1) + 1,
+                    _ => return Err(ParseError::new(s, &line[idx..idx + 1], "'.' or '#'")),
+                };
+            }
+            if line.len() != 10 {
+                return Err(ParseError::new(s, line, "a row of 10 tiles"));
+            }
+
+            count += 1;
+        }
+
+        if count < 10 {
+            return Err(ParseError::end_of_input(s, "10 rows of tiles"));
+        }
+
+        Ok(Tile10(id, rows))
+    }
+}
+
+// Written the same way as it's parsed
+impl fmt::Display for Tile10 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Tile {}:", self.0)?;
+        for row in self.1.iter() {
+            writeln!(f)?;
+            for idx in (0..10).rev() {
+                write!(f, "{}", if row >> idx & 1 == 1 { '#' } else { '.' })?;
             }
         }
-        Err(())
+
+        Ok(())
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
 pub struct Tile8([u8; 8]);
 
 impl Tile8 {
@@ -144,10 +185,10 @@ impl Picture {
     pub fn from_tile_8_arrays(tiles: [[Tile8; 12]; 12]) -> Picture {
         let mut pixels = [0; 96];
 
-        for i in 0..12 {
-            for j in 0..12 {
-                for k in 0..8 {
-                    pixels[8 * i + k] |= (tiles[i][j].0[k] as u128) << ((11 - j) * 8);
+        for (i, row) in tiles.iter().enumerate() {
+            for (j, tile) in row.iter().enumerate() {
+                for (k, tile_row) in tile.0.iter().enumerate() {
+                    pixels[8 * i + k] |= (*tile_row as u128) << ((11 - j) * 8);
                 }
             }
         }
@@ -176,16 +217,22 @@ impl Picture {
     }
 }
 
+impl Default for Picture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl D4 for Picture {
     fn rotate(&self, n: usize) -> Picture {
-        let mut rows: [u128; 96] = self.0.clone();
+        let mut rows: [u128; 96] = self.0;
 
         for _ in 0..(n % 4) {
             let mut rotated: [u128; 96] = [0; 96];
 
-            for i in 0..96 {
+            for (i, rotated_row) in rotated.iter_mut().enumerate() {
                 for j in 0..96 {
-                    rotated[i] |= (1 & (rows[95 - j] >> (95 - i))) << (95 - j);
+                    *rotated_row |= (1 & (rows[95 - j] >> (95 - i))) << (95 - j);
                 }
             }
 
@@ -196,9 +243,9 @@ impl D4 for Picture {
     }
 
     fn mirror(&self, n: usize) -> Picture {
-        let mut rows: [u128; 96] = self.0.clone();
+        let mut rows: [u128; 96] = self.0;
         for _ in 0..(n % 2) {
-            let current_rows = rows.clone();
+            let current_rows = rows;
 
             for (i, row) in current_rows.iter().rev().enumerate() {
                 rows[i] = *row;
@@ -209,9 +256,372 @@ impl D4 for Picture {
     }
 }
 
+// The number of tiles in a jigsaw, which is always 12x12
+const TILES: usize = 144;
+
+// Far more random walks than it takes to assemble any jigsaw which can be assembled, which usually needs a few
+// hundred at most
+const MAX_ATTEMPTS: usize = 20_000;
+
+// Assembles a 12x12 grid of tiles by repeated random walks from the bottom-right corner, each of which picks a
+// random orientation of a tile consistent with its right and lower neighbours until either the grid is filled or
+// the walk gets stuck.  Gives up with None if none of the walks fill the grid, as when the tiles don't fit together.
+pub fn assemble(tiles: &[Tile10]) -> Option<[[Tile10; 12]; 12]> {
+    if tiles.len() != TILES {
+        return None;
+    }
+
+    let symmetries = tiles
+        .iter()
+        .map(|tile| tile.symmetries())
+        .collect::<Vec<_>>();
+
+    let mut neighbours_to_left = HashMap::new();
+    let mut neighbours_to_up = HashMap::new();
+
+    for (orientation, neighbour_map) in [1, 2]
+        .iter()
+        .zip([&mut neighbours_to_left, &mut neighbours_to_up].iter_mut())
+    {
+        symmetries
+            .iter()
+            .enumerate()
+            .flat_map(|(i, tiles)| {
+                tiles
+                    .iter()
+                    .enumerate()
+                    .map(move |(j, tile)| (tile.signature()[*orientation], (i, j)))
+            })
+            .for_each(|(signature, xy)| {
+                let neighbours = neighbour_map.entry(signature).or_insert_with(HashSet::new);
+                neighbours.insert(xy);
+            })
+    }
+
+    let path = (0..12)
+        .flat_map(|x| (0..12).map(move |y| (x, y)))
+        .collect::<Vec<_>>();
+
+    let mut rng = rand::thread_rng();
+    for attempt in 1..=MAX_ATTEMPTS {
+        let mut random_walk_grid: [[Option<Tile10>; 12]; 12] = [[None; 12]; 12];
+        let mut random_walk_path = path.clone();
+        let mut random_walk_seen = HashSet::new();
+        while !random_walk_path.is_empty() {
+            match random_walk_path.pop().unwrap() {
+                (11, 11) => {
+                    let random_walk_start =
+                        symmetries[rng.gen_range(0..TILES)][rng.gen_range(0..8)];
+                    random_walk_seen.insert(random_walk_start.id());
+                    random_walk_grid[11][11] = Some(random_walk_start);
+                }
+                (11, col) => {
+                    let random_walk_candidates_from_right = neighbours_to_left
+                        .get(&random_walk_grid[11][col + 1].unwrap().signature()[3]);
+                    match random_walk_candidates_from_right {
+                        Some(random_walk_candidates_to_right) => {
+                            let filtered_random_walk_candidates = random_walk_candidates_to_right
+                                .iter()
+                                .map(|(i, j)| symmetries[*i][*j])
+                                .filter(|tile| !random_walk_seen.contains(&tile.id()))
+                                .collect::<Vec<_>>();
+
+                            if let Some(next_tile) =
+                                filtered_random_walk_candidates.choose(&mut rng)
+                            {
+                                random_walk_seen.insert(next_tile.id());
+                                random_walk_grid[11][col] = Some(*next_tile);
+                            } else {
+                                break;
+                            }
+                        }
+                        _ => break,
+                    }
+                }
+                (row, 11) => {
+                    let random_walk_candidates_from_down = neighbours_to_up
+                        .get(&random_walk_grid[row + 1][11].unwrap().signature()[0]);
+                    match random_walk_candidates_from_down {
+                        Some(random_walk_candidates_from_down) => {
+                            let filtered_random_walk_candidates = random_walk_candidates_from_down
+                                .iter()
+                                .map(|(i, j)| symmetries[*i][*j])
+                                .filter(|tile| !random_walk_seen.contains(&tile.id()))
+                                .collect::<Vec<_>>();
+
+                            if let Some(next_tile) =
+                                filtered_random_walk_candidates.choose(&mut rng)
+                            {
+                                random_walk_seen.insert(next_tile.id());
+                                random_walk_grid[row][11] = Some(*next_tile);
+                            } else {
+                                break;
+                            }
+                        }
+                        _ => break,
+                    }
+                }
+                (row, col) => {
+                    let random_walk_candidates_from_down = neighbours_to_up
+                        .get(&random_walk_grid[row + 1][col].unwrap().signature()[0]);
+                    let random_walk_candidates_from_right = neighbours_to_left
+                        .get(&random_walk_grid[row][col + 1].unwrap().signature()[3]);
+
+                    match (
+                        random_walk_candidates_from_down,
+                        random_walk_candidates_from_right,
+                    ) {
+                        (
+                            Some(random_walk_candidates_from_down),
+                            Some(random_walk_candidates_from_right),
+                        ) => {
+                            let filtered_random_walk_candidates = random_walk_candidates_from_down
+                                .intersection(random_walk_candidates_from_right)
+                                .map(|(i, j)| symmetries[*i][*j])
+                                .filter(|tile| !random_walk_seen.contains(&tile.id()))
+                                .collect::<Vec<_>>();
+
+                            if let Some(next_tile) =
+                                filtered_random_walk_candidates.choose(&mut rng)
+                            {
+                                random_walk_seen.insert(next_tile.id());
+                                random_walk_grid[row][col] = Some(*next_tile);
+                            } else {
+                                break;
+                            }
+                        }
+                        _ => break,
+                    }
+                }
+            }
+        }
+
+        trace::step("walk", |fields| {
+            fields
+                .with("attempt", attempt)
+                .with("placed", random_walk_seen.len())
+        });
+        if random_walk_path.is_empty() {
+            trace::summary("assembled", |fields| fields.with("attempts", attempt));
+            let mut grid: [[Tile10; 12]; 12] = [[random_walk_grid[0][0].unwrap(); 12]; 12];
+            for (row, random_walk_row) in grid.iter_mut().zip(random_walk_grid.iter()) {
+                for (tile, random_walk_tile) in row.iter_mut().zip(random_walk_row.iter()) {
+                    *tile = random_walk_tile.unwrap();
+                }
+            }
+            return Some(grid);
+        }
+    }
+
+    trace::summary("gave up", |fields| fields.with("attempts", MAX_ATTEMPTS));
+    None
+}
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = Vec<Tile10>;
+
+    const DAY: u8 = 20;
+
+    fn parse(input: &str) -> Result<Vec<Tile10>, ParseError> {
+        let blocks: Vec<&str> = input
+            .split("\n\n")
+            .filter(|s| !s.trim().is_empty())
+            .collect();
+        let tiles = blocks
+            .iter()
+            .map(|s| s.parse::<Tile10>().map_err(|err| err.within(input, s)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match blocks.get(TILES) {
+            Some(extra) => Err(ParseError::new(input, extra, "no more than 144 tiles")),
+            None if tiles.len() < TILES => Err(ParseError::end_of_input(input, "144 tiles")),
+            None => Ok(tiles),
+        }
+    }
+
+    fn part1(tiles: &Vec<Tile10>) -> Answer {
+        let grid = match assemble(tiles) {
+            Some(grid) => grid,
+            None => return Answer::Unsolved,
+        };
+        let corners = [grid[0][0], grid[0][11], grid[11][0], grid[11][11]];
+        trace::summary("corners", |fields| {
+            fields.with("ids", corners.iter().map(Tile10::id).collect::<Vec<_>>())
+        });
+
+        corners.iter().map(Tile10::id).product::<usize>().into()
+    }
+
+    fn part2(tiles: &Vec<Tile10>) -> Answer {
+        let grid = match assemble(tiles) {
+            Some(grid) => grid,
+            None => return Answer::Unsolved,
+        };
+
+        let mut inner_grid: [[Tile8; 12]; 12] = [[Tile8::new(); 12]; 12];
+        for (inner_row, row) in inner_grid.iter_mut().zip(grid.iter()) {
+            for (inner_tile, tile) in inner_row.iter_mut().zip(row.iter()) {
+                *inner_tile = tile.inner();
+            }
+        }
+
+        let picture = Picture::from_tile_8_arrays(inner_grid);
+
+        let roughness = picture
+            .symmetries()
+            .iter()
+            .enumerate()
+            .map(|(idx, sym)| {
+                let roughness = sym.choppiness();
+                trace::step("orientation", |fields| {
+                    fields.with("symmetry", idx).with("roughness", roughness)
+                });
+                roughness
+            })
+            .min();
+        if let Some(roughness) = roughness {
+            let ones = picture.0.iter().map(|x| x.count_ones()).sum::<u32>();
+            let sea_monster_ones = SEA_MONSTER.iter().map(|x| x.count_ones()).sum::<u32>();
+            trace::summary("sea monsters", |fields| {
+                fields
+                    .with("count", (ones - roughness) / sea_monster_ones)
+                    .with("roughness", roughness)
+            });
+        }
+
+        roughness.into()
+    }
+}
+
+// A side of a tile read the other way round
+fn reverse_edge(edge: u16) -> u16 {
+    edge.reverse_bits() >> 6
+}
+
+// The pixels of a whole jigsaw, where neighbouring tiles overlap along their shared edge.  Every edge is different
+// from every other, read in either direction, so that the tiles can only be assembled in one way.
+fn jigsaw_pixels(rng: &mut StdRng) -> Option<Vec<Vec<bool>>> {
+    let side = 9 * 12 + 1;
+    let mut pixels = vec![vec![false; side]; side];
+    for row in (0..side).step_by(9) {
+        for col in (0..side).step_by(9) {
+            pixels[row][col] = rng.gen();
+        }
+    }
+
+    let mut used = HashSet::new();
+    let mut choose_edge = |start: bool, end: bool| {
+        (0..1000).find_map(|_| {
+            let edge = (start as u16) << 9 | rng.gen_range(0..256) << 1 | end as u16;
+            let reversed = reverse_edge(edge);
+            if edge != reversed && used.insert(edge.min(reversed)) {
+                Some(edge)
+            } else {
+                None
+            }
+        })
+    };
+
+    for i in (0..side).step_by(9) {
+        for j in (0..side - 1).step_by(9) {
+            let edge = choose_edge(pixels[i][j], pixels[i][j + 9])?;
+            for k in 1..9 {
+                pixels[i][j + k] = edge >> (9 - k) & 1 == 1;
+            }
+
+            let edge = choose_edge(pixels[j][i], pixels[j + 9][i])?;
+            for k in 1..9 {
+                pixels[j + k][i] = edge >> (9 - k) & 1 == 1;
+            }
+        }
+    }
+
+    Some(pixels)
+}
+
+impl Generator for Day20 {
+    const DEFAULT_SIZE: usize = 12;
+
+    // Always a 12x12 jigsaw, as that's all the assembly handles.  The image is sparse enough that sea monsters are
+    // only found where they were planted.
+    fn generate(rng: &mut StdRng, _size: usize) -> Generated {
+        let mut pixels = loop {
+            if let Some(pixels) = jigsaw_pixels(rng) {
+                break pixels;
+            }
+        };
+
+        // The image is everything other than the tiles' edges
+        let image_pixel = |n: usize| 9 * (n / 8) + 1 + n % 8;
+        for row in 0..96 {
+            for col in 0..96 {
+                pixels[image_pixel(row)][image_pixel(col)] = rng.gen_bool(0.2);
+            }
+        }
+
+        let monster: Vec<(usize, usize)> = SEA_MONSTER
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| {
+                (0..20)
+                    .filter(move |j| row >> (19 - j) & 1 == 1)
+                    .map(move |j| (i, j))
+            })
+            .collect();
+        let mut covered = HashSet::new();
+        let mut monsters = 0;
+        for _ in 0..rng.gen_range(5..20) {
+            let (row, col) = (rng.gen_range(0..96 - 2), rng.gen_range(0..96 - 19));
+            let cells: Vec<_> = monster.iter().map(|(i, j)| (row + i, col + j)).collect();
+            if cells.iter().any(|cell| covered.contains(cell)) {
+                continue;
+            }
+
+            for &(i, j) in cells.iter() {
+                pixels[image_pixel(i)][image_pixel(j)] = true;
+                covered.insert((i, j));
+            }
+            monsters += 1;
+        }
+
+        let roughness = (0..96)
+            .flat_map(|row| (0..96).map(move |col| (row, col)))
+            .filter(|&(row, col)| pixels[image_pixel(row)][image_pixel(col)])
+            .count()
+            - monsters * monster.len();
+
+        let mut ids: Vec<usize> = (1000..10000).collect();
+        ids.shuffle(rng);
+
+        let mut tiles = Vec::with_capacity(144);
+        for (i, id) in ids.iter().take(144).enumerate() {
+            let (row, col) = (9 * (i / 12), 9 * (i % 12));
+            let mut rows = [0; 10];
+            for (k, tile_row) in rows.iter_mut().enumerate() {
+                *tile_row = (0..10).fold(0, |a, l| (a << 1) | pixels[row + k][col + l] as u16);
+            }
+
+            tiles.push(Tile10(*id, rows).symmetries()[rng.gen_range(0..8)]);
+        }
+        let corners: usize = [0, 11, 132, 143].iter().map(|&i| tiles[i].id()).product();
+        tiles.shuffle(rng);
+
+        let input = tiles
+            .iter()
+            .map(|tile| tile.to_string())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        Generated::new(input).part_1(corners).part_2(roughness)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_signature() {
@@ -399,12 +809,7 @@ mod tests {
 
     #[test]
     fn test_grid_construction() {
-        let mut tiles: [[Tile8; 12]; 12] = [[const_tile_8(0); 12]; 12];
-        for i in 0..12 {
-            for j in 0..12 {
-                tiles[i][j] = const_tile_8(12 * i as u8 + j as u8)
-            }
-        }
+        let tiles = numbered_tiles();
 
         let grid = Picture::from_tile_8_arrays(tiles);
 
@@ -424,14 +829,19 @@ mod tests {
         Tile8([x, x, x, x, x, x, x, x])
     }
 
-    #[test]
-    fn test_grid_d4() {
+    fn numbered_tiles() -> [[Tile8; 12]; 12] {
         let mut tiles: [[Tile8; 12]; 12] = [[const_tile_8(0); 12]; 12];
-        for i in 0..12 {
-            for j in 0..12 {
-                tiles[i][j] = const_tile_8(12 * i as u8 + j as u8)
+        for (i, row) in tiles.iter_mut().enumerate() {
+            for (j, tile) in row.iter_mut().enumerate() {
+                *tile = const_tile_8(12 * i as u8 + j as u8)
             }
         }
+        tiles
+    }
+
+    #[test]
+    fn test_grid_d4() {
+        let tiles = numbered_tiles();
 
         let grid = Picture::from_tile_8_arrays(tiles);
 
@@ -448,12 +858,7 @@ mod tests {
 
     #[test]
     fn test_grid_d4_distinct() {
-        let mut tiles: [[Tile8; 12]; 12] = [[const_tile_8(0); 12]; 12];
-        for i in 0..12 {
-            for j in 0..12 {
-                tiles[i][j] = const_tile_8(12 * i as u8 + j as u8)
-            }
-        }
+        let tiles = numbered_tiles();
 
         let grid = Picture::from_tile_8_arrays(tiles);
 
@@ -479,4 +884,102 @@ mod tests {
 
         assert_eq!(picture.choppiness(), 0);
     }
+
+    #[test]
+    fn test_parse_error() {
+        let tile = "Tile 7:\n..........\n..........\n..........\n..........\n..........\n";
+        let input = format!(
+            "{}..........\n..........\n....x.....\n..........\n..........",
+            tile
+        );
+
+        let err = Day20::parse(&input).unwrap_err();
+        assert_eq!((err.line, err.column), (9, 5));
+        assert_eq!(err.expected, "'.' or '#'");
+
+        let err = Day20::parse(&format!("{}..........", tile)).unwrap_err();
+        assert_eq!((err.line, err.column), (7, 11));
+        assert_eq!(err.expected, "10 rows of tiles");
+
+        let err = Day20::parse("Tile seven:\n").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 6));
+
+        let err = Day20::parse("").unwrap_err();
+        assert_eq!(err.expected, "144 tiles");
+
+        let tile = format!("Tile 7:\n{}", [".........."; 10].join("\n"));
+        let err = Day20::parse(&[tile.as_str(); 3].join("\n\n")).unwrap_err();
+        assert_eq!(err.expected, "144 tiles");
+
+        let err = Day20::parse(&vec![tile.as_str(); 145].join("\n\n")).unwrap_err();
+        assert_eq!((err.line, err.column), (144 * 12 + 1, 1));
+        assert_eq!(err.expected, "no more than 144 tiles");
+    }
+
+    #[test]
+    fn test_unassemblable() {
+        let tile = Tile10(7, [0; 10]);
+        assert_eq!(assemble(&[tile; 3]), None);
+
+        // Changing a pixel in the middle of each of a tile's sides leaves it fitting nowhere
+        let generated = common::generate::generate::<Day20>(12, 20);
+        let mut tiles = Day20::parse(&generated.input).unwrap();
+        let Tile10(_, rows) = &mut tiles[0];
+        rows[0] ^= 1 << 5;
+        rows[9] ^= 1 << 5;
+        rows[5] ^= 1 << 9 | 1;
+        assert_eq!(Day20::part1(&tiles), Answer::Unsolved);
+    }
+
+    // The symmetries of anything with a D4 action are closed under the action, and there are 8 of them unless the
+    // thing is itself symmetric
+    fn check_d4<T: D4 + PartialEq + fmt::Debug>(x: T) -> Result<(), TestCaseError> {
+        let quarter_turns = (0..4).fold(x, |y, _| y.rotate(1));
+        prop_assert_eq!(quarter_turns, x);
+        prop_assert_eq!(x.mirror(1).mirror(1), x);
+        prop_assert_eq!(x.mirror(1).rotate(1), x.rotate(3).mirror(1));
+
+        let symmetries = x.symmetries();
+        for y in symmetries.iter() {
+            for z in y.symmetries().iter() {
+                prop_assert!(symmetries.contains(z));
+            }
+        }
+
+        let distinct = (0..8)
+            .filter(|&i| !symmetries[..i].contains(&symmetries[i]))
+            .count();
+        prop_assert_eq!(8 % distinct, 0);
+
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn prop_tile_symmetries(id in 0..10000usize, rows in prop::array::uniform10(0..1024u16)) {
+            check_d4(Tile10(id, rows))?;
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn prop_picture_symmetries(rows in prop::collection::vec(0..1u128 << 96, 96)) {
+            let mut pixels = [0; 96];
+            pixels.copy_from_slice(&rows);
+            check_d4(Picture(pixels))?;
+        }
+    }
+
+    #[test]
+    fn test_asymmetric_tile() {
+        let mut rows = [0; 10];
+        rows[0] = 0b0100000000;
+        let symmetries = Tile10(0, rows).symmetries();
+
+        for i in 0..8 {
+            assert!(!symmetries[..i].contains(&symmetries[i]));
+        }
+    }
 }
@@ -0,0 +1,203 @@
+use std::collections::{HashMap, HashSet};
+
+use common::{
+    error::parse_lines,
+    trace::{self, Level},
+    Answer, ParseError, Solution,
+};
+
+#[derive(Debug)]
+pub struct Food {
+    pub ingredients: HashSet<String>,
+    pub allergens: HashSet<String>,
+}
+
+// Returns the number of times each ingredient appears across all foods, and the allergen contained in each of the
+// ingredients which could be identified.
+pub fn identify(foods: &[Food]) -> (HashMap<&str, usize>, HashMap<&str, &str>) {
+    let mut allergen_potential_ingredients = HashMap::new();
+    let mut ingredient_potential_allergens = HashMap::new();
+    let mut ingredient_count: HashMap<_, usize> = HashMap::new();
+
+    for food in foods {
+        let ingredients = food
+            .ingredients
+            .iter()
+            .map(String::as_str)
+            .collect::<HashSet<_>>();
+        let allergens = food
+            .allergens
+            .iter()
+            .map(String::as_str)
+            .collect::<HashSet<_>>();
+        for ingredient in ingredients.clone() {
+            *ingredient_count.entry(ingredient).or_insert(0) += 1;
+
+            let allergen_set = ingredient_potential_allergens
+                .entry(ingredient)
+                .or_insert(allergens.clone());
+            *allergen_set = allergen_set
+                .union(&allergens)
+                .copied()
+                .collect::<HashSet<_>>();
+        }
+        for allergen in allergens {
+            let ingredient_set = allergen_potential_ingredients
+                .entry(allergen)
+                .or_insert(ingredients.clone());
+            *ingredient_set = ingredient_set
+                .intersection(&ingredients)
+                .copied()
+                .collect::<HashSet<_>>();
+        }
+    }
+
+    let mut ones = allergen_potential_ingredients
+        .iter()
+        .filter(|(_, s)| s.len() == 1)
+        .map(|(s, _)| *s)
+        .collect::<Vec<&str>>();
+
+    let mut ingredient_allergen_names = HashMap::new();
+    while let Some(allergen) = ones.pop() {
+        let ingredient_one_set = allergen_potential_ingredients
+            .get(allergen)
+            .unwrap()
+            .clone();
+
+        let ingredient = *ingredient_one_set.iter().next().unwrap();
+
+        allergen_potential_ingredients.remove(allergen);
+        ingredient_allergen_names.insert(ingredient, allergen);
+        trace::step("pair", |fields| {
+            fields
+                .with("allergen", allergen)
+                .with("ingredient", ingredient)
+        });
+
+        let allergen_set = ingredient_potential_allergens.get(ingredient).unwrap();
+
+        for allerg in allergen_set {
+            if let Some(ingredient_set) = allergen_potential_ingredients.get_mut(*allerg) {
+                ingredient_set.remove(ingredient);
+                if ingredient_set.len() == 1 {
+                    ones.push(allerg);
+                }
+            }
+        }
+    }
+
+    (ingredient_count, ingredient_allergen_names)
+}
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = Vec<Food>;
+
+    const DAY: u8 = 21;
+
+    // Foods without a list of allergens tell us nothing, so they are skipped
+    fn parse(input: &str) -> Result<Vec<Food>, ParseError> {
+        let foods = parse_lines(input, |line| {
+            let line = line.trim_end();
+            let (ingredients_list, allergens_list) = match line.split_once('(') {
+                Some(lists) => lists,
+                None => return Ok(None),
+            };
+
+            let allergens_list = allergens_list.strip_prefix("contains ").ok_or_else(|| {
+                ParseError::new(line, allergens_list, "\"contains\" and a list of allergens")
+            })?;
+            let allergens_list = allergens_list
+                .strip_suffix(')')
+                .ok_or_else(|| ParseError::end_of_input(line, "')'"))?;
+
+            Ok(Some(Food {
+                ingredients: ingredients_list
+                    .split_ascii_whitespace()
+                    .map(|s| s.to_string())
+                    .collect(),
+                allergens: allergens_list.split(", ").map(|s| s.to_string()).collect(),
+            }))
+        })?;
+
+        Ok(foods.into_iter().flatten().collect())
+    }
+
+    fn part1(foods: &Vec<Food>) -> Answer {
+        let (ingredient_count, ingredient_allergen_names) = identify(foods);
+
+        let mut part_1: usize = ingredient_count.values().sum();
+        for ingredient in ingredient_allergen_names.keys() {
+            part_1 -= ingredient_count.get(ingredient).unwrap();
+        }
+        trace::summary("safe", |fields| {
+            fields
+                .with(
+                    "ingredients",
+                    ingredient_count.len() - ingredient_allergen_names.len(),
+                )
+                .with("appearances", part_1)
+        });
+
+        part_1.into()
+    }
+
+    fn part2(foods: &Vec<Food>) -> Answer {
+        let (_, ingredient_allergen_names) = identify(foods);
+
+        let mut part_2 = ingredient_allergen_names
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+        part_2.sort_by(|a, b| {
+            ingredient_allergen_names
+                .get(a)
+                .unwrap()
+                .cmp(ingredient_allergen_names.get(b).unwrap())
+        });
+        if trace::enabled(Level::Summary) {
+            for ingredient in part_2.iter() {
+                trace::summary("paired", |fields| {
+                    fields
+                        .with("allergen", ingredient_allergen_names[ingredient])
+                        .with("ingredient", *ingredient)
+                });
+            }
+        }
+
+        part_2.join(",").into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let input = "mxmxvkd kfcds sqjhc nhms (contains dairy, fish)
+trh fvjkl sbzzf mxmxvkd (contains dairy)
+sqjhc fvjkl (contains soy)
+sqjhc mxmxvkd sbzzf (contains fish)";
+
+        let foods = Day21::parse(input).unwrap();
+        assert_eq!(Day21::part1(&foods), Answer::Number(5));
+        assert_eq!(
+            Day21::part2(&foods),
+            Answer::Text("mxmxvkd,sqjhc,fvjkl".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = Day21::parse("a b (contains dairy)\nc d (dairy)").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 6));
+        assert_eq!(err.snippet, "dairy)");
+
+        let err = Day21::parse("a b (contains dairy").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 20));
+        assert_eq!(err.expected, "')'");
+    }
+}
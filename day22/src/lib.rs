@@ -1,5 +1,6 @@
 use std::collections::{HashSet, VecDeque};
 
+use common::{error::parse_number, trace, Answer, ParseError, Solution};
 use sha2::{Digest, Sha256};
 
 #[derive(Debug, Clone, Copy)]
@@ -20,6 +21,15 @@ pub enum GameState {
     Complete(Player, u32),
 }
 
+impl GameState {
+    pub fn winner(&self) -> Option<Player> {
+        match self {
+            GameState::InProgress => None,
+            GameState::Complete(player, _) => Some(*player),
+        }
+    }
+}
+
 pub struct Game {
     mode: GameMode,
     state: GameState,
@@ -31,8 +41,8 @@ pub struct Game {
 impl Game {
     pub fn new(mode: GameMode, p1_hand: &[u32], p2_hand: &[u32]) -> Game {
         let state = GameState::InProgress;
-        let p1_hand = p1_hand.iter().map(|x| *x).collect();
-        let p2_hand = p2_hand.iter().map(|x| *x).collect();
+        let p1_hand = p1_hand.iter().copied().collect();
+        let p2_hand = p2_hand.iter().copied().collect();
         let seen_signatures = HashSet::new();
         Game {
             mode,
@@ -97,6 +107,18 @@ impl Game {
                                 seen_signatures: HashSet::new(),
                             });
                             sub_game.play();
+                            trace::step("sub-game", |fields| {
+                                fields
+                                    .with("cards", vec![u, v])
+                                    .with("rounds", sub_game.seen_signatures.len())
+                                    .with(
+                                        "winner",
+                                        sub_game
+                                            .state()
+                                            .winner()
+                                            .map(|player| format!("{:?}", player)),
+                                    )
+                            });
                             match sub_game.state() {
                                 GameState::InProgress => panic!("Shouldn't match this"),
                                 GameState::Complete(Player::P1, _) => {
@@ -182,3 +204,104 @@ impl Game {
         (p1_component, p2_component)
     }
 }
+
+#[derive(Debug)]
+pub struct Decks {
+    pub player_1: Vec<u32>,
+    pub player_2: Vec<u32>,
+}
+
+fn parse_deck(input: &str, header: &str) -> Result<Vec<u32>, ParseError> {
+    let mut lines = input.lines();
+
+    match lines.next() {
+        Some(line) if line.trim_end() == header => {}
+        Some(line) => return Err(ParseError::new(input, line, format!("{:?}", header))),
+        None => return Err(ParseError::end_of_input(input, format!("{:?}", header))),
+    }
+
+    lines.map(|line| parse_number(input, line.trim())).collect()
+}
+
+pub fn winning_score(mode: GameMode, decks: &Decks) -> u32 {
+    let mut game = Game::new(mode, &decks.player_1, &decks.player_2);
+    game.play();
+    match game.state() {
+        GameState::Complete(winner, score) => {
+            trace::summary("winner", |fields| {
+                fields
+                    .with("player", format!("{:?}", winner))
+                    .with("rounds", game.seen_signatures.len())
+                    .with("score", score)
+            });
+            score
+        }
+        _ => panic!("Shouldn't happen"),
+    }
+}
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input = Decks;
+
+    const DAY: u8 = 22;
+
+    fn parse(input: &str) -> Result<Decks, ParseError> {
+        let (player_1_input, player_2_input) = input.split_once("\n\n").ok_or_else(|| {
+            ParseError::end_of_input(input, "a blank line followed by player 2's deck")
+        })?;
+
+        Ok(Decks {
+            player_1: parse_deck(player_1_input, "Player 1:")
+                .map_err(|err| err.within(input, player_1_input))?,
+            player_2: parse_deck(player_2_input.trim_end(), "Player 2:")
+                .map_err(|err| err.within(input, player_2_input))?,
+        })
+    }
+
+    fn part1(decks: &Decks) -> Answer {
+        winning_score(GameMode::Basic, decks).into()
+    }
+
+    fn part2(decks: &Decks) -> Answer {
+        winning_score(GameMode::Recursive, decks).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let input = "Player 1:
+9
+2
+6
+3
+1
+
+Player 2:
+5
+8
+4
+7
+10";
+
+        let decks = Day22::parse(input).unwrap();
+        assert_eq!(Day22::part1(&decks), Answer::Number(306));
+        assert_eq!(Day22::part2(&decks), Answer::Number(291));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = Day22::parse("Player 1:\n9\n2\n\nPlayer 2:\n5\nten").unwrap_err();
+        assert_eq!((err.line, err.column), (7, 1));
+        assert_eq!(err.expected, "a number");
+
+        let err = Day22::parse("Player 1:\n9\n2\n\nPlayer 3:\n5").unwrap_err();
+        assert_eq!((err.line, err.column), (5, 1));
+        assert_eq!(err.expected, "\"Player 2:\"");
+    }
+}
@@ -0,0 +1,185 @@
+use common::{
+    trace::{self, Level},
+    Answer, ParseError, Solution,
+};
+
+// Only the first moves of a game are traced, as the long game has ten million of them
+const TRACED_MOVES: usize = 1000;
+
+pub fn play(values: &[usize], rounds: usize) -> Vec<usize> {
+    let max_val = values.iter().max().unwrap();
+
+    let mut next_cups = vec![0; *max_val + 1];
+    for (val, next_val) in values
+        .iter()
+        .cycle()
+        .zip(values.iter().cycle().skip(1))
+        .take(values.len())
+    {
+        next_cups[*val] = *next_val
+    }
+
+    let tracing = trace::enabled(Level::Steps);
+    let mut round = 0;
+    let mut cup = values[0];
+    while round < rounds {
+        let next_cup_1 = next_cups[cup];
+        let next_cup_2 = next_cups[next_cup_1];
+        let next_cup_3 = next_cups[next_cup_2];
+
+        let mut dest_cup = cup - 1;
+        if dest_cup == 0 {
+            dest_cup = *max_val;
+        }
+
+        while dest_cup == next_cup_1 || dest_cup == next_cup_2 || dest_cup == next_cup_3 {
+            dest_cup -= 1;
+            if dest_cup == 0 {
+                dest_cup = *max_val;
+            }
+        }
+
+        if tracing && round < TRACED_MOVES {
+            trace::step("move", |fields| {
+                fields
+                    .with("number", round + 1)
+                    .with("current", cup)
+                    .with("pick_up", vec![next_cup_1, next_cup_2, next_cup_3])
+                    .with("destination", dest_cup)
+            });
+        }
+
+        let next_round_cup = next_cups[next_cup_3];
+
+        next_cups[next_cup_3] = next_cups[dest_cup];
+        next_cups[dest_cup] = next_cups[cup];
+        next_cups[cup] = next_round_cup;
+
+        cup = next_round_cup;
+        round += 1;
+    }
+
+    let mut result = Vec::new();
+
+    let mut cup = values[0];
+    while result.len() < values.len() {
+        result.push(cup);
+        cup = next_cups[cup];
+    }
+
+    result
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Input = Vec<usize>;
+
+    const DAY: u8 = 23;
+
+    // Part 2's million cups, along with the table of which cup follows each
+    const MEMORY: usize = 2 * 1_000_000 * std::mem::size_of::<usize>();
+
+    // The cups must be labelled 1 to 9, each exactly once, as part 2 adds the cups from 10 onwards
+    fn parse(input: &str) -> Result<Vec<usize>, ParseError> {
+        let labels = input.trim();
+
+        let mut cups = Vec::new();
+        for (idx, c) in labels.char_indices() {
+            match c.to_digit(10).map(|d| d as usize) {
+                Some(cup) if cup > 0 && !cups.contains(&cup) => cups.push(cup),
+                _ => {
+                    return Err(ParseError::new(
+                        input,
+                        &labels[idx..idx + c.len_utf8()],
+                        "an unused cup label from 1 to 9",
+                    ))
+                }
+            }
+        }
+
+        if cups.len() < 9 {
+            return Err(ParseError::new(
+                input,
+                &labels[labels.len()..],
+                "9 cup labels",
+            ));
+        }
+
+        Ok(cups)
+    }
+
+    fn part1(cups: &Vec<usize>) -> Answer {
+        let part_1 = play(cups, 100);
+
+        let labels = part_1
+            .iter()
+            .cycle()
+            .skip_while(|x| **x != 1)
+            .skip(1)
+            .take(cups.len() - 1)
+            .map(|x| x.to_string())
+            .collect::<String>();
+        trace::summary("after cup 1", |fields| {
+            fields.with("labels", labels.as_str())
+        });
+
+        labels.into()
+    }
+
+    fn part2(cups: &Vec<usize>) -> Answer {
+        let mut vals_part_2 = cups.clone();
+        for x in 10..=1000000 {
+            vals_part_2.push(x);
+        }
+
+        let part_2 = play(&vals_part_2, 10000000);
+
+        let stars: Vec<usize> = part_2
+            .iter()
+            .cycle()
+            .skip_while(|x| **x != 1)
+            .skip(1)
+            .take(2)
+            .copied()
+            .collect();
+        trace::summary("after cup 1", |fields| fields.with("cups", stars.clone()));
+
+        stars.iter().product::<usize>().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_play() {
+        let cups = play(&Day23::parse("389125467").unwrap(), 10);
+        let labels = cups
+            .iter()
+            .cycle()
+            .skip_while(|x| **x != 1)
+            .skip(1)
+            .take(8)
+            .map(|x| x.to_string())
+            .collect::<String>();
+
+        assert_eq!(labels, "92658374");
+        assert_eq!(
+            Day23::part1(&Day23::parse("389125467").unwrap()),
+            Answer::Text("67384529".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = Day23::parse("389125437").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 8));
+        assert_eq!(err.snippet, "3");
+
+        let err = Day23::parse("38912546").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 9));
+        assert_eq!(err.expected, "9 cup labels");
+    }
+}
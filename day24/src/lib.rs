@@ -1,4 +1,16 @@
-use std::ops::{Add, AddAssign, Mul};
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    ops::{Add, AddAssign, Mul},
+};
+
+use common::{
+    automaton::{Automaton, Rule},
+    error::parse_lines,
+    trace,
+    visualize::{Glyphs, Layout, Placement, Player, Visualize},
+    Answer, ParseError, Solution,
+};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub struct CyclotomicInteger(pub i64, pub i64);
@@ -35,9 +47,150 @@ impl Mul for CyclotomicInteger {
     }
 }
 
+pub static EAST: CyclotomicInteger = CyclotomicInteger(1, 0);
+pub static NORTH_EAST: CyclotomicInteger = CyclotomicInteger(0, 1);
+pub static NORTH_WEST: CyclotomicInteger = CyclotomicInteger(-1, 1);
+pub static WEST: CyclotomicInteger = CyclotomicInteger(-1, 0);
+pub static SOUTH_WEST: CyclotomicInteger = CyclotomicInteger(0, -1);
+pub static SOUTH_EAST: CyclotomicInteger = CyclotomicInteger(1, -1);
+
+pub static DIRECTIONS: [CyclotomicInteger; 6] =
+    [EAST, NORTH_EAST, NORTH_WEST, WEST, SOUTH_WEST, SOUTH_EAST];
+
+// Follows a line of directions from the reference tile, returning the tile it ends on
+pub fn destination(line: &str) -> Result<CyclotomicInteger, ParseError> {
+    let directions = line.trim();
+
+    let mut steps: Vec<CyclotomicInteger> = Vec::new();
+    let mut cs = directions.char_indices();
+    while let Some((idx, c)) = cs.next() {
+        let step = match c {
+            'e' => EAST,
+            'w' => WEST,
+            'n' | 's' => match (c, cs.next()) {
+                ('n', Some((_, 'e'))) => NORTH_EAST,
+                ('n', Some((_, 'w'))) => NORTH_WEST,
+                ('s', Some((_, 'e'))) => SOUTH_EAST,
+                ('s', Some((_, 'w'))) => SOUTH_WEST,
+                (_, Some((next_idx, next))) => {
+                    return Err(ParseError::new(
+                        line,
+                        &directions[next_idx..next_idx + next.len_utf8()],
+                        "'e' or 'w'",
+                    ))
+                }
+                (_, None) => {
+                    return Err(ParseError::new(
+                        line,
+                        &directions[directions.len()..],
+                        "'e' or 'w'",
+                    ))
+                }
+            },
+            _ => {
+                return Err(ParseError::new(
+                    line,
+                    &directions[idx..idx + c.len_utf8()],
+                    "one of \"e\", \"se\", \"sw\", \"w\", \"nw\" or \"ne\"",
+                ))
+            }
+        };
+        steps.push(step);
+    }
+
+    Ok(steps.iter().fold(CyclotomicInteger(0, 0), |a, s| a + *s))
+}
+
+pub fn black_tiles(destinations: &[CyclotomicInteger]) -> HashSet<CyclotomicInteger> {
+    let mut destination_counts = HashMap::new();
+
+    for destination in destinations {
+        let e = destination_counts.entry(*destination).or_insert(0);
+        *e += 1;
+    }
+
+    let black = destination_counts
+        .iter()
+        .filter(|(_, c)| *c & 1 == 1)
+        .map(|(t, _)| *t)
+        .collect::<HashSet<_>>();
+    trace::summary("flipped", |fields| {
+        fields
+            .with("tiles", destination_counts.len())
+            .with("black", black.len())
+    });
+
+    black
+}
+
+pub struct Day24;
+
+impl Solution for Day24 {
+    type Input = Vec<CyclotomicInteger>;
+
+    const DAY: u8 = 24;
+
+    fn parse(input: &str) -> Result<Vec<CyclotomicInteger>, ParseError> {
+        parse_lines(input, destination)
+    }
+
+    fn part1(destinations: &Vec<CyclotomicInteger>) -> Answer {
+        black_tiles(destinations).len().into()
+    }
+
+    fn part2(destinations: &Vec<CyclotomicInteger>) -> Answer {
+        lobby(destinations).run(DAYS).into()
+    }
+}
+
+const DAYS: usize = 100;
+
+fn adjacent(z: CyclotomicInteger) -> [CyclotomicInteger; 6] {
+    DIRECTIONS.map(|dz| z + dz)
+}
+
+// The lobby's living art exhibit, with the black tiles being active
+fn lobby(
+    destinations: &[CyclotomicInteger],
+) -> Automaton<CyclotomicInteger, fn(CyclotomicInteger) -> [CyclotomicInteger; 6]> {
+    Automaton::new(
+        black_tiles(destinations),
+        adjacent,
+        Rule::new(&[2], &[1, 2]),
+    )
+}
+
+// Hexagonal tiles drawn with north upwards, where each row is offset by half a tile from the next, so that the tiles
+// are in every other column
+pub struct Hex;
+
+impl Layout<CyclotomicInteger> for Hex {
+    // u + v * zeta is u + v / 2 tiles east, and v rows north
+    fn place(&self, z: &CyclotomicInteger) -> Placement {
+        Placement {
+            panel: Vec::new(),
+            row: -z.1,
+            col: 2 * z.0 + z.1,
+        }
+    }
+
+    fn is_cell(&self, row: i64, col: i64) -> bool {
+        (row + col).rem_euclid(2) == 0
+    }
+}
+
+impl Visualize for Day24 {
+    fn visualize(destinations: &Vec<CyclotomicInteger>, player: &mut Player) -> io::Result<()> {
+        let glyphs = Glyphs::default();
+        player.watch("Lobby", &mut lobby(destinations), &Hex, glyphs, Some(DAYS))?;
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::CyclotomicInteger;
+    use crate::{destination, CyclotomicInteger};
 
     #[test]
     pub fn test_multiplication() {
@@ -52,4 +205,18 @@ mod tests {
             CyclotomicInteger(1, 0)
         );
     }
+
+    #[test]
+    pub fn test_destination_error() {
+        let err = destination("esxnee").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 3));
+        assert_eq!(err.snippet, "x");
+
+        let err = destination("nwwsn").unwrap_err();
+        assert_eq!(err.column, 5);
+        assert_eq!(err.expected, "'e' or 'w'");
+
+        let err = destination("nwx").unwrap_err();
+        assert_eq!(err.column, 3);
+    }
 }
@@ -0,0 +1,103 @@
+use common::{
+    error::parse_number,
+    generate::{Generated, Generator, Rng, StdRng},
+    numtheory::ModInt,
+    trace, Answer, ParseError, Solution,
+};
+
+const P: u64 = 20201227;
+
+type Residue = ModInt<P>;
+
+fn parse_public_key(input: &str, line: Option<&str>) -> Result<u64, ParseError> {
+    let line = line.ok_or_else(|| ParseError::end_of_input(input, "a public key"))?;
+    let public_key = parse_number(input, line.trim())?;
+
+    if public_key >= P {
+        return Err(ParseError::new(
+            input,
+            line.trim(),
+            format!("a public key less than {}", P),
+        ));
+    }
+
+    Ok(public_key)
+}
+
+// The loop size is the discrete log of a public key, which is then used to transform the other public key
+pub fn encryption_key(card_public_key: u64, door_public_key: u64) -> Option<u64> {
+    let card_loop_size = Residue::from(7).log(card_public_key.into())?;
+    trace::summary("loop size", |fields| fields.with("card", card_loop_size));
+
+    Some(Residue::from(door_public_key).pow(card_loop_size).value())
+}
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Input = (u64, u64);
+
+    const DAY: u8 = 25;
+
+    fn parse(input: &str) -> Result<(u64, u64), ParseError> {
+        let mut lines = input.lines();
+
+        let card_public_key = parse_public_key(input, lines.next())?;
+        let door_public_key = parse_public_key(input, lines.next())?;
+
+        Ok((card_public_key, door_public_key))
+    }
+
+    fn part1(&(card_public_key, door_public_key): &(u64, u64)) -> Answer {
+        encryption_key(card_public_key, door_public_key).into()
+    }
+
+    // There is no second puzzle on the last day
+    fn part2(_: &(u64, u64)) -> Answer {
+        Answer::Unsolved
+    }
+}
+
+impl Generator for Day25 {
+    const DEFAULT_SIZE: usize = P as usize - 2;
+
+    // The size is the largest loop size, and the encryption key is found by transforming 7 by both loop sizes
+    fn generate(rng: &mut StdRng, size: usize) -> Generated {
+        let size = size.clamp(1, P as usize - 2) as u64;
+        let card_loop_size = rng.gen_range(1..=size);
+        let door_loop_size = rng.gen_range(1..=size);
+
+        let subject = Residue::from(7);
+        let input = format!(
+            "{}\n{}",
+            subject.pow(card_loop_size),
+            subject.pow(door_loop_size)
+        );
+
+        Generated::new(input)
+            .part_1(subject.pow(card_loop_size * door_loop_size))
+            .part_2("-")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encryption_key() {
+        assert_eq!(encryption_key(5764801, 17807724), Some(14897079));
+        assert_eq!(encryption_key(0, 17807724), None);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = Day25::parse("5764801\n20201227\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.expected, "a public key less than 20201227");
+
+        let err = Day25::parse("5764801\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.expected, "a public key");
+    }
+}
@@ -1,71 +1,548 @@
-use std::{error::Error, str::FromStr};
+use std::str::FromStr;
 
+use common::{
+    graph::Graph,
+    grid::{Cell, Point},
+    trace, Answer, Grid, ParseError, Solution,
+};
+
+// The puzzle's maps only have open ground and trees, but other terrain can be crossed too, at a different cost
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Tile {
     Open,
     Tree,
+    Ice,
+    Snow,
+    Rock,
 }
 
-#[derive(Debug)]
-pub struct ParseMapError {
-    details: String,
-}
+impl Cell for Tile {
+    const EXPECTED: &'static str = "'.', '#', '~', '*' or '^'";
+    const NAME: &'static str = "tile";
+
+    fn from_char(c: char) -> Option<Tile> {
+        match c {
+            '.' => Some(Tile::Open),
+            '#' => Some(Tile::Tree),
+            '~' => Some(Tile::Ice),
+            '*' => Some(Tile::Snow),
+            '^' => Some(Tile::Rock),
+            _ => None,
+        }
+    }
 
-impl std::fmt::Display for ParseMapError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.details)
+    fn to_char(&self) -> char {
+        match self {
+            Tile::Open => '.',
+            Tile::Tree => '#',
+            Tile::Ice => '~',
+            Tile::Snow => '*',
+            Tile::Rock => '^',
+        }
     }
 }
 
-impl Error for ParseMapError {
-    fn description(&self) -> &str {
-        &self.details
+impl Tile {
+    // What it costs to cross the tile, or None if it can't be crossed at all.  A toboggan can go through a tree, but
+    // it hurts.
+    pub fn cost(self) -> Option<u32> {
+        match self {
+            Tile::Ice => Some(0),
+            Tile::Open => Some(1),
+            Tile::Snow => Some(3),
+            Tile::Tree => Some(10),
+            Tile::Rock => None,
+        }
     }
 }
 
-impl ParseMapError {
-    fn new(msg: &str) -> ParseMapError {
-        ParseMapError {
-            details: msg.to_string(),
+// What lies beyond the edges of the map
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Boundary {
+    // The map repeats to the right, as in the puzzle, but not downwards
+    WrapHorizontal,
+    WrapBoth,
+    Hard,
+}
+
+impl FromStr for Boundary {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "wrap" => Ok(Boundary::WrapHorizontal),
+            "wrap-both" => Ok(Boundary::WrapBoth),
+            "hard" => Ok(Boundary::Hard),
+            _ => Err("Expected --boundary wrap, wrap-both or hard".to_string()),
         }
     }
 }
 
 #[derive(Debug)]
 pub struct TobogganMap {
-    tiles: Vec<Vec<Tile>>,
+    tiles: Grid<Tile>,
+    boundary: Boundary,
 }
 
 impl FromStr for TobogganMap {
-    type Err = ParseMapError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut tiles: Vec<Vec<Tile>> = Vec::new();
-        for line in s.lines() {
-            let row: Result<Vec<Tile>, Self::Err> = line
-                .chars()
-                .map(|char| match char {
-                    '.' => Ok(Tile::Open),
-                    '#' => Ok(Tile::Tree),
-                    _ => Err(ParseMapError::new(&format!("Invalid character: {}", char))),
-                })
-                .collect();
-            match row {
-                Ok(tile) => tiles.push(tile),
-                Err(err) => return Err(err),
-            }
-        }
-        Ok(TobogganMap { tiles })
+        Ok(TobogganMap {
+            tiles: s.parse()?,
+            boundary: Boundary::WrapHorizontal,
+        })
     }
 }
 
 impl TobogganMap {
-    pub fn get(&self, x: usize, y: usize) -> Tile {
-        let cols = self.tiles[0].len();
-        self.tiles[y][x % cols]
+    pub fn with_boundary(self, boundary: Boundary) -> TobogganMap {
+        TobogganMap { boundary, ..self }
+    }
+
+    // The tile at a point, or None if the point is beyond an edge which doesn't wrap
+    pub fn get(&self, x: usize, y: usize) -> Option<Tile> {
+        let (width, height) = (self.tiles.width(), self.tiles.height());
+        match self.boundary {
+            Boundary::WrapHorizontal if y < height => Some(self.tiles[(x % width, y)]),
+            Boundary::WrapBoth => Some(self.tiles[(x % width, y % height)]),
+            Boundary::Hard if x < width && y < height => Some(self.tiles[(x, y)]),
+            _ => None,
+        }
     }
 
     pub fn rows(&self) -> usize {
-        self.tiles.len()
+        self.tiles.height()
+    }
+
+    // The tiles visited going `dx` right and `dy` down at a time from the top left, until past the bottom of the map
+    // or over an edge which doesn't wrap.  Even a map which wraps downwards is only descended once.
+    pub fn path(&self, dx: usize, dy: usize) -> Path<'_> {
+        Path {
+            toboggan_map: self,
+            dx,
+            dy,
+            x: 0,
+            y: 0,
+        }
+    }
+}
+
+// The coordinates of each tile on a path, where `x` keeps counting to the right rather than wrapping around, along
+// with the tile.  A path which doesn't go down ends after its first tile, rather than crossing the top row forever,
+// and one whose coordinates would no longer fit in a `usize` ends there too.
+pub struct Path<'a> {
+    toboggan_map: &'a TobogganMap,
+    dx: usize,
+    dy: usize,
+    x: usize,
+    y: usize,
+}
+
+impl Iterator for Path<'_> {
+    type Item = ((usize, usize), Tile);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.y >= self.toboggan_map.rows() {
+            return None;
+        }
+
+        let (x, y) = (self.x, self.y);
+        let tile = self.toboggan_map.get(x, y)?;
+        match (x.checked_add(self.dx), y.checked_add(self.dy)) {
+            (Some(x), Some(y)) if self.dy > 0 => {
+                self.x = x;
+                self.y = y;
+            }
+            _ => self.y = self.toboggan_map.rows(),
+        }
+
+        Some(((x, y), tile))
+    }
+}
+
+pub fn trees_on_slope(toboggan_map: &TobogganMap, dx: usize, dy: usize) -> u32 {
+    let mut trees = 0;
+    for ((x, y), tile) in toboggan_map.path(dx, dy) {
+        if tile == Tile::Tree {
+            trace::step("tree", |fields| fields.with("x", x).with("y", y));
+            trees += 1;
+        }
+    }
+
+    trace::summary("slope", |fields| {
+        fields
+            .with("right", dx)
+            .with("down", dy)
+            .with("trees", trees)
+    });
+
+    trees
+}
+
+// Every slope going up to `max_dx` right and between 1 and `max_dy` down, with the number of trees on it, from the
+// fewest trees to the most.  Slopes with as many trees as each other are ordered by how far right and then down
+// they go.
+pub fn rank_slopes(
+    toboggan_map: &TobogganMap,
+    max_dx: usize,
+    max_dy: usize,
+) -> Vec<((usize, usize), u32)> {
+    let mut slopes: Vec<((usize, usize), u32)> = (0..=max_dx)
+        .flat_map(|dx| (1..=max_dy).map(move |dy| (dx, dy)))
+        .map(|(dx, dy)| ((dx, dy), trees_on_slope(toboggan_map, dx, dy)))
+        .collect();
+    slopes.sort_by_key(|&(slope, trees)| (trees, slope));
+
+    slopes
+}
+
+// A tile of a map with a route drawn over it, as in the puzzle: the route is marked `O` where it was clear and `X`
+// where it hit a tree or a rock
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Mark {
+    Tile(Tile),
+    Clear,
+    Hit,
+}
+
+impl Cell for Mark {
+    const EXPECTED: &'static str = "'.', '#', '~', '*', '^', 'O' or 'X'";
+    const NAME: &'static str = "tile";
+
+    fn from_char(c: char) -> Option<Mark> {
+        match c {
+            'O' => Some(Mark::Clear),
+            'X' => Some(Mark::Hit),
+            _ => Tile::from_char(c).map(Mark::Tile),
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Mark::Tile(tile) => tile.to_char(),
+            Mark::Clear => 'O',
+            Mark::Hit => 'X',
+        }
+    }
+}
+
+impl Mark {
+    fn colour(self) -> [u8; 3] {
+        match self {
+            Mark::Tile(Tile::Open) => [236, 240, 241],
+            Mark::Tile(Tile::Tree) => [39, 174, 96],
+            Mark::Tile(Tile::Ice) => [174, 214, 241],
+            Mark::Tile(Tile::Snow) => [255, 255, 255],
+            Mark::Tile(Tile::Rock) => [127, 140, 141],
+            Mark::Clear => [52, 152, 219],
+            Mark::Hit => [192, 57, 43],
+        }
+    }
+}
+
+// The most columns a drawn route can take up, as steep slopes would otherwise repeat the map beyond what fits in memory
+pub const MAX_ROUTE_WIDTH: usize = 1 << 16;
+
+// The map, repeated to the right as many times as it takes to fit the whole of the route taking the slope, unless
+// that would be wider than `MAX_ROUTE_WIDTH`
+pub fn route(toboggan_map: &TobogganMap, dx: usize, dy: usize) -> Result<Grid<Mark>, String> {
+    let steps = match dy {
+        0 => 0,
+        dy => toboggan_map.rows().saturating_sub(1) / dy,
+    };
+    match dx.checked_mul(steps) {
+        Some(furthest) if furthest < MAX_ROUTE_WIDTH => {}
+        _ => {
+            return Err(format!(
+                "A slope of {},{} is too steep to draw in at most {} columns",
+                dx, dy, MAX_ROUTE_WIDTH
+            ))
+        }
+    }
+
+    let path: Vec<(usize, usize)> = toboggan_map.path(dx, dy).map(|(xy, _)| xy).collect();
+    let furthest = path.iter().map(|&(x, _)| x).max().unwrap_or(0);
+    let width = toboggan_map.tiles.width();
+
+    let mut marks = Grid::from_fn(
+        (furthest / width + 1) * width,
+        toboggan_map.rows(),
+        |(x, y)| Mark::Tile(toboggan_map.tiles[(x % width, y)]),
+    );
+    for xy in path {
+        marks[xy] = match marks[xy] {
+            Mark::Tile(Tile::Tree) | Mark::Tile(Tile::Rock) => Mark::Hit,
+            _ => Mark::Clear,
+        };
+    }
+
+    Ok(marks)
+}
+
+// The most pixels a drawn route's image can have, as with `MAX_ROUTE_WIDTH`
+pub const MAX_PPM_PIXELS: usize = 1 << 26;
+
+// A binary PPM image of a drawn route, with each tile as a square `scale` pixels across, unless that would have more
+// than `MAX_PPM_PIXELS`
+pub fn to_ppm(marks: &Grid<Mark>, scale: usize) -> Result<Vec<u8>, String> {
+    let scale = scale.max(1);
+    let (width, height) = match (
+        marks.width().checked_mul(scale),
+        marks.height().checked_mul(scale),
+    ) {
+        (Some(width), Some(height))
+            if width
+                .checked_mul(height)
+                .is_some_and(|pixels| pixels <= MAX_PPM_PIXELS) =>
+        {
+            (width, height)
+        }
+        _ => {
+            return Err(format!(
+                "A scale of {} is too large to draw in at most {} pixels",
+                scale, MAX_PPM_PIXELS
+            ))
+        }
+    };
+    let mut image = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+
+    for row in marks.rows() {
+        for _ in 0..scale {
+            for mark in row {
+                for _ in 0..scale {
+                    image.extend_from_slice(&mark.colour());
+                }
+            }
+        }
+    }
+
+    Ok(image)
+}
+
+// The cheapest way down from the top left to anywhere on the bottom row, going a tile left, right or down at a time,
+// where `cost` gives the cost of crossing each tile (or None where it can't be crossed).  The total includes the
+// tiles at either end, and the route is given as the points it goes through.
+pub fn cheapest_descent(
+    toboggan_map: &TobogganMap,
+    cost: impl Fn(Tile) -> Option<u32>,
+) -> Option<(u32, Vec<Point>)> {
+    let (width, height) = (toboggan_map.tiles.width(), toboggan_map.rows());
+    let tile_cost = |(x, y): Point| toboggan_map.get(x, y).and_then(&cost);
+
+    // Points are kept within the map, with any wrapping done when moving left or right
+    let wraps = toboggan_map.boundary != Boundary::Hard;
+    let mut slopes: Graph<Point, u32> = Graph::new();
+    for (x, y) in toboggan_map.tiles.points() {
+        if tile_cost((x, y)).is_none() {
+            continue;
+        }
+        slopes.add_node((x, y));
+
+        let left = match x {
+            0 if wraps => Some(width - 1),
+            0 => None,
+            _ => Some(x - 1),
+        };
+        let right = match x + 1 {
+            x if x < width => Some(x),
+            _ if wraps => Some(0),
+            _ => None,
+        };
+        let moves = [
+            left.map(|x| (x, y)),
+            right.map(|x| (x, y)),
+            Some((x, y + 1)).filter(|&(_, y)| y < height),
+        ];
+        for to in moves.iter().flatten() {
+            if let Some(weight) = tile_cost(*to) {
+                slopes.add_edge((x, y), *to, weight);
+            }
+        }
+    }
+
+    let start_cost = tile_cost((0, 0))?;
+    let (weight, route) = slopes.shortest_path(&(0, 0), |&(_, y)| y + 1 == height)?;
+    let route: Vec<Point> = route.into_iter().copied().collect();
+    trace::summary("descent", |fields| {
+        fields
+            .with("cost", start_cost + weight)
+            .with("tiles", route.len())
+    });
+
+    Some((start_cost + weight, route))
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = TobogganMap;
+    const DAY: u8 = 3;
+
+    fn parse(input: &str) -> Result<TobogganMap, ParseError> {
+        input.parse()
+    }
+
+    fn part1(toboggan_map: &TobogganMap) -> Answer {
+        trees_on_slope(toboggan_map, 3, 1).into()
+    }
+
+    fn part2(toboggan_map: &TobogganMap) -> Answer {
+        [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)]
+            .iter()
+            .map(|(dx, dy)| trees_on_slope(toboggan_map, *dx, *dy))
+            .product::<u32>()
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let input = "..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#";
+
+        let toboggan_map = Day3::parse(input).unwrap();
+        assert_eq!(Day3::part1(&toboggan_map), Answer::Number(7));
+        assert_eq!(Day3::part2(&toboggan_map), Answer::Number(336));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = "..#\n.x.".parse::<TobogganMap>().unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        assert_eq!(err.expected, "'.', '#', '~', '*' or '^'");
+
+        let err = "..#\n..".parse::<TobogganMap>().unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.expected, "a row of 3 tiles");
+    }
+
+    #[test]
+    fn test_slopes() {
+        let toboggan_map: TobogganMap = "..#\n#..\n.#.\n..#".parse().unwrap();
+
+        assert_eq!(
+            toboggan_map.path(2, 1).collect::<Vec<_>>(),
+            vec![
+                ((0, 0), Tile::Open),
+                ((2, 1), Tile::Open),
+                ((4, 2), Tile::Tree),
+                ((6, 3), Tile::Open)
+            ]
+        );
+        assert_eq!(
+            toboggan_map
+                .path(1, 2)
+                .map(|(xy, _)| xy)
+                .collect::<Vec<_>>(),
+            vec![(0, 0), (1, 2)]
+        );
+        assert_eq!(toboggan_map.path(1, 0).count(), 1);
+
+        assert_eq!(trees_on_slope(&toboggan_map, 1, 1), 0);
+        assert_eq!(trees_on_slope(&toboggan_map, 0, 1), 1);
+        assert_eq!(trees_on_slope(&toboggan_map, usize::MAX, 1), 1);
+        assert_eq!(toboggan_map.path(usize::MAX, 1).count(), 2);
+        assert_eq!(toboggan_map.path(1, usize::MAX).count(), 1);
+
+        let ranked = rank_slopes(&toboggan_map, 2, 2);
+        assert_eq!(ranked.len(), 6);
+        assert_eq!(ranked[0], ((0, 2), 0));
+        assert_eq!(ranked[5], ((2, 1), 1));
+        assert!(ranked.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+    }
+
+    #[test]
+    fn test_route() {
+        let toboggan_map: TobogganMap = "..#\n#..\n.#.\n..#".parse().unwrap();
+
+        let marks = route(&toboggan_map, 2, 1).unwrap();
+        assert_eq!(
+            marks.to_string(),
+            "O.#..#..#\n#.O#..#..\n.#..X..#.\n..#..#O.#"
+        );
+        assert_eq!(
+            marks,
+            "O.#..#..#\n#.O#..#..\n.#..X..#.\n..#..#O.#"
+                .parse()
+                .unwrap()
+        );
+        assert_eq!(
+            route(&toboggan_map, 0, 2).unwrap().to_string(),
+            "O.#\n#..\nO#.\n..#"
+        );
+        assert!(route(&toboggan_map, 10_000_000_000_000, 1).is_err());
+        assert!(route(&toboggan_map, usize::MAX, 1).is_err());
+
+        let image = to_ppm(&marks, 2).unwrap();
+        let header = b"P6\n18 8\n255\n";
+        assert_eq!(&image[..header.len()], header);
+        assert_eq!(image.len(), header.len() + 18 * 8 * 3);
+        assert_eq!(
+            &image[header.len()..header.len() + 6],
+            &[52, 152, 219, 52, 152, 219]
+        );
+        assert!(to_ppm(&marks, usize::MAX).is_err());
+        assert!(to_ppm(&marks, 100_000).is_err());
+    }
+
+    #[test]
+    fn test_boundaries() {
+        let toboggan_map: TobogganMap = "..#\n#..\n.#.\n..#".parse().unwrap();
+        assert_eq!(toboggan_map.get(4, 2), Some(Tile::Tree));
+        assert_eq!(toboggan_map.get(0, 4), None);
+
+        let toboggan_map = toboggan_map.with_boundary(Boundary::WrapBoth);
+        assert_eq!(toboggan_map.get(3, 5), Some(Tile::Tree));
+        assert_eq!(toboggan_map.path(1, 1).count(), 4);
+
+        let toboggan_map = toboggan_map.with_boundary(Boundary::Hard);
+        assert_eq!(toboggan_map.get(4, 2), None);
+        assert_eq!(toboggan_map.get(1, 2), Some(Tile::Tree));
+        assert_eq!(
+            toboggan_map
+                .path(2, 1)
+                .map(|(xy, _)| xy)
+                .collect::<Vec<_>>(),
+            vec![(0, 0), (2, 1)]
+        );
+        assert_eq!(trees_on_slope(&toboggan_map, 1, 1), 0);
+    }
+
+    #[test]
+    fn test_cheapest_descent() {
+        let toboggan_map: TobogganMap = ".^~~\n.^*^\n.#~.\n^^.^".parse().unwrap();
+
+        // Wrapping around to the right saves going through the tree
+        assert_eq!(
+            cheapest_descent(&toboggan_map, Tile::cost),
+            Some((5, vec![(0, 0), (3, 0), (2, 0), (2, 1), (2, 2), (2, 3)]))
+        );
+
+        let toboggan_map = toboggan_map.with_boundary(Boundary::Hard);
+        assert_eq!(
+            cheapest_descent(&toboggan_map, Tile::cost),
+            Some((14, vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2), (2, 3)]))
+        );
+        assert_eq!(
+            cheapest_descent(&toboggan_map, |tile| match tile {
+                Tile::Tree => None,
+                tile => tile.cost(),
+            }),
+            None
+        );
     }
 }
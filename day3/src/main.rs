@@ -1,45 +1,126 @@
-use std::io::{self, Read};
+use std::{
+    env,
+    io::{self, Write},
+    path::PathBuf,
+    process,
+};
 
-use day3::{Tile, TobogganMap};
+use common::{input::InputSource, Solution};
+use day3::{route, to_ppm, Boundary, Day3};
 
-fn main() -> io::Result<()> {
-    let mut buffer = String::new();
-    io::stdin().read_to_string(&mut buffer)?;
+// The most pixels across that each tile can be drawn
+const MAX_SCALE: usize = 64;
 
-    let toboggan_map: TobogganMap = buffer.parse().unwrap();
+static USAGE: &str =
+    "Usage: day3 render [--slope RIGHT,DOWN] [--boundary wrap|wrap-both|hard] [--format text|ppm] [--scale N] [PATH|-]";
 
-    // Part 1
-    let mut trees_encountered = 0;
-    let (mut x, mut y) = (0, 0);
+enum Format {
+    Text,
+    Ppm,
+}
 
-    while y < toboggan_map.rows() {
-        if toboggan_map.get(x, y) == Tile::Tree {
-            trees_encountered += 1;
-        }
-        x += 3;
-        y += 1;
-    }
+struct RenderArgs {
+    slope: (usize, usize),
+    boundary: Boundary,
+    format: Format,
+    scale: usize,
+    path: Option<PathBuf>,
+}
 
-    println!("{}", trees_encountered);
+fn parse_slope(slope: &str) -> Option<(usize, usize)> {
+    let idx = slope.find(',')?;
+    Some((slope[..idx].parse().ok()?, slope[idx + 1..].parse().ok()?))
+}
 
-    // Part 2
-    let mut trees_encountered_product: u32 = 1;
-    for (dx, dy) in vec![(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)] {
-        let mut trees_encountered = 0;
-        let (mut x, mut y) = (0, 0);
+fn parse_render_args(args: &[String]) -> Result<RenderArgs, String> {
+    let mut slope = (3, 1);
+    let mut boundary = Boundary::WrapHorizontal;
+    let mut format = Format::Text;
+    let mut scale = 4;
+    let mut path = None;
 
-        while y < toboggan_map.rows() {
-            if toboggan_map.get(x, y) == Tile::Tree {
-                trees_encountered += 1;
-            }
-            x += dx;
-            y += dy;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--slope" => match args.next().and_then(|slope| parse_slope(slope)) {
+                Some(parsed) => slope = parsed,
+                None => return Err("Expected a slope such as --slope 3,1".to_string()),
+            },
+            "--boundary" => match args.next() {
+                Some(name) => boundary = name.parse()?,
+                None => return Err("Expected --boundary wrap, wrap-both or hard".to_string()),
+            },
+            "--format" => match args.next().map(String::as_str) {
+                Some("text") => format = Format::Text,
+                Some("ppm") => format = Format::Ppm,
+                _ => return Err("Expected --format text or --format ppm".to_string()),
+            },
+            "--scale" => match args.next().map(|s| s.parse::<usize>()) {
+                Some(Ok(n)) if n > 0 && n <= MAX_SCALE => scale = n,
+                _ => {
+                    return Err(format!(
+                        "Expected a number from 1 to {} after --scale",
+                        MAX_SCALE
+                    ))
+                }
+            },
+            _ if path.is_none() => path = Some(PathBuf::from(arg)),
+            _ => return Err(format!("Unexpected argument: {}", arg)),
         }
+    }
+
+    Ok(RenderArgs {
+        slope,
+        boundary,
+        format,
+        scale,
+        path,
+    })
+}
 
-        trees_encountered_product *= trees_encountered;
+fn main() {
+    let source = InputSource::new(Day3::DAY).embedded(include_str!("../input"));
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().map(String::as_str) != Some("render") {
+        common::solution::main::<Day3>(source);
+        return;
     }
 
-    println!("{}", trees_encountered_product);
+    let args = parse_render_args(&args[1..]).unwrap_or_else(|err| {
+        eprintln!("{}\n\n{}", err, USAGE);
+        process::exit(2);
+    });
+    let toboggan_map = source
+        .path(args.path)
+        .piped_stdin()
+        .read()
+        .map_err(|err| err.to_string())
+        .and_then(|input| {
+            Day3::parse(&input).map_err(|err| format!("unable to parse input: {}", err))
+        })
+        .unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            process::exit(1);
+        });
 
-    Ok(())
+    let (dx, dy) = args.slope;
+    let marks = route(&toboggan_map.with_boundary(args.boundary), dx, dy).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
+    let written = match args.format {
+        Format::Text => writeln!(io::stdout(), "{}", marks),
+        Format::Ppm => match to_ppm(&marks, args.scale) {
+            Ok(image) => io::stdout().write_all(&image),
+            Err(err) => {
+                eprintln!("{}", err);
+                process::exit(1);
+            }
+        },
+    };
+    if let Err(err) = written {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
 }
@@ -0,0 +1,243 @@
+use std::collections::{HashMap, HashSet};
+use std::iter::FromIterator;
+
+use common::{
+    parser::{groups, lines, map, parse, separated, take_while1, try_map},
+    trace, Answer, ParseError, Solution,
+};
+
+pub trait ValidatesPassport {
+    fn validate(&self, passport: &HashMap<String, String>) -> bool;
+}
+
+pub struct Part1Validator {
+    required_keys: HashSet<String>,
+}
+
+impl Part1Validator {
+    pub fn new(required_keys: HashSet<String>) -> Part1Validator {
+        Part1Validator { required_keys }
+    }
+}
+
+impl ValidatesPassport for Part1Validator {
+    fn validate(&self, passport: &HashMap<String, String>) -> bool {
+        let keys: HashSet<String> = HashSet::from_iter(passport.keys().map(|k| k.to_string()));
+        self.required_keys.is_subset(&keys)
+    }
+}
+
+pub enum Field {
+    Year(i32, i32),
+    Measure(String, i32, i32),
+    Hex,
+    OneOf(HashSet<String>),
+    Number(usize),
+}
+
+pub struct Part2Validator {
+    required_keys: HashMap<String, Vec<Field>>,
+}
+
+impl Part2Validator {
+    pub fn new(required_keys: HashMap<String, Vec<Field>>) -> Part2Validator {
+        Part2Validator { required_keys }
+    }
+}
+
+impl ValidatesPassport for Part2Validator {
+    fn validate(&self, passport: &HashMap<String, String>) -> bool {
+        let keys: HashSet<String> = HashSet::from_iter(passport.keys().map(|k| k.to_string()));
+        for (key, fields) in self.required_keys.iter() {
+            if !keys.contains(key) {
+                return false;
+            }
+            let val = passport.get(key).unwrap();
+            let valid = fields.iter().any(|field| match field {
+                Field::Year(min, max) => {
+                    if let Ok(year) = val.parse::<i32>() {
+                        return *min <= year && year <= *max;
+                    }
+                    false
+                }
+                Field::Measure(suffix, min, max) => {
+                    let chars = val.chars();
+                    let (bbb, ccc): (String, String) = chars.partition(|c| c.is_numeric());
+                    if ccc != *suffix {
+                        return false;
+                    }
+                    if let Ok(bbbb) = bbb.parse::<i32>() {
+                        return *min <= bbbb && bbbb <= *max;
+                    }
+
+                    false
+                }
+                Field::Hex => {
+                    if val.len() != 7 {
+                        return false;
+                    }
+                    let mut chars = val.chars();
+                    if chars.next().unwrap() != '#' {
+                        return false;
+                    }
+                    for char in chars {
+                        if !"0123456789abcdef".contains(char) {
+                            return false;
+                        }
+                    }
+
+                    true
+                }
+                Field::OneOf(items) => items.contains(val),
+                Field::Number(digits) => {
+                    if val.len() != *digits {
+                        return false;
+                    }
+                    for c in val.chars() {
+                        if !c.is_numeric() {
+                            return false;
+                        }
+                    }
+                    true
+                }
+            });
+
+            if !valid {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+// Passports are separated by blank lines, and each has fields of the form "key:value" separated by spaces or newlines
+pub fn passports(input: &str) -> Result<Vec<HashMap<String, String>>, ParseError> {
+    let field = try_map(
+        take_while1(|c| !c.is_whitespace(), "a field"),
+        |kv_pair: &str| match kv_pair.split_once(':') {
+            Some((k, v)) if !k.is_empty() && !v.is_empty() => Ok((k.to_string(), v.to_string())),
+            _ => Err("a field of the form \"key:value\"".to_string()),
+        },
+    );
+    let passport = map(lines(separated(field, " ")), |lines| {
+        lines.into_iter().flatten().collect()
+    });
+
+    parse(input, groups(passport))
+}
+
+pub fn part_1_validator() -> Part1Validator {
+    Part1Validator::new(HashSet::from_iter(
+        ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"]
+            .iter()
+            .map(|s| s.to_string()),
+    ))
+}
+
+pub fn part_2_validator() -> Part2Validator {
+    let mut part_2_required_fields: HashMap<String, Vec<Field>> = HashMap::new();
+    part_2_required_fields.insert(String::from("byr"), vec![Field::Year(1920, 2002)]);
+    part_2_required_fields.insert(String::from("iyr"), vec![Field::Year(2010, 2020)]);
+    part_2_required_fields.insert(String::from("eyr"), vec![Field::Year(2020, 2030)]);
+    part_2_required_fields.insert(
+        String::from("hgt"),
+        vec![
+            Field::Measure(String::from("cm"), 150, 193),
+            Field::Measure(String::from("in"), 59, 76),
+        ],
+    );
+    part_2_required_fields.insert(String::from("hcl"), vec![Field::Hex]);
+    part_2_required_fields.insert(
+        String::from("ecl"),
+        vec![Field::OneOf(HashSet::from_iter(
+            ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"]
+                .iter()
+                .map(|s| s.to_string()),
+        ))],
+    );
+    part_2_required_fields.insert(String::from("pid"), vec![Field::Number(9)]);
+
+    Part2Validator::new(part_2_required_fields)
+}
+
+// The number of passports which `validator` accepts
+fn count_valid(passports: &[HashMap<String, String>], validator: &impl ValidatesPassport) -> usize {
+    let mut valid = 0;
+    for (idx, passport) in passports.iter().enumerate() {
+        if validator.validate(passport) {
+            valid += 1;
+        } else {
+            trace::step("invalid", |fields| fields.with("passport", idx + 1));
+        }
+    }
+    trace::summary("valid", |fields| {
+        fields.with("count", valid).with("of", passports.len())
+    });
+
+    valid
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Vec<HashMap<String, String>>;
+
+    const DAY: u8 = 4;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        passports(input)
+    }
+
+    fn part1(passports: &Self::Input) -> Answer {
+        count_valid(passports, &part_1_validator()).into()
+    }
+
+    fn part2(passports: &Self::Input) -> Answer {
+        count_valid(passports, &part_2_validator()).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let input = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183cm
+
+iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
+hcl:#cfa07d byr:1929
+
+hcl:#ae17e1 iyr:2013
+eyr:2024
+ecl:brn pid:760753108 byr:1931
+hgt:179cm
+
+hcl:#cfa07d eyr:2025 pid:166559648
+iyr:2011 ecl:brn hgt:59in";
+
+        let passports = Day4::parse(input).unwrap();
+        assert_eq!(Day4::part1(&passports), Answer::Number(2));
+    }
+
+    #[test]
+    fn test_part_2_validator() {
+        let invalid = "eyr:1972 cid:100
+hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926";
+        let valid = "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980
+hcl:#623a2f";
+
+        let validator = part_2_validator();
+        assert!(!validator.validate(&passports(invalid).unwrap()[0]));
+        assert!(validator.validate(&passports(valid).unwrap()[0]));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = passports("ecl:gry pid:860033327\n\nbyr:1937 iyr2017").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 10));
+        assert_eq!(err.snippet, "iyr2017");
+    }
+}
@@ -1,54 +1,263 @@
-pub fn compute_seat_id(boarding_pass: &str) -> Result<u32, String> {
-    let chars = boarding_pass.chars();
+use std::{io::BufRead, iter::FromIterator};
 
+use common::{
+    error::parse_lines,
+    generate::{Generated, Generator, Rng, SliceRandom, StdRng},
+    stream::{self, StreamError, Streaming},
+    trace, Answer, ParseError, Solution,
+};
+
+pub fn compute_seat_id(boarding_pass: &str) -> Result<u32, ParseError> {
     let mut count = 0;
     let mut seat_id = 0;
-    for (foo, char) in chars.enumerate() {
-        count += 1;
+    for (idx, (byte_idx, char)) in boarding_pass.char_indices().enumerate() {
+        let snippet = &boarding_pass[byte_idx..byte_idx + char.len_utf8()];
         seat_id <<= 1;
-        match (foo < 7, char) {
-            (true, 'B') => seat_id |= 1,
-            (true, 'F') => {}
-            (false, 'R') => seat_id |= 1,
-            (false, 'L') => {}
-            _ => return Err(format!("Invalid character: {}", char)),
+        match (idx, char) {
+            (0..=6, 'B') => seat_id |= 1,
+            (0..=6, 'F') => {}
+            (0..=6, _) => return Err(ParseError::new(boarding_pass, snippet, "'F' or 'B'")),
+            (7..=9, 'R') => seat_id |= 1,
+            (7..=9, 'L') => {}
+            (7..=9, _) => return Err(ParseError::new(boarding_pass, snippet, "'L' or 'R'")),
+            _ => {
+                return Err(ParseError::new(
+                    boarding_pass,
+                    &boarding_pass[byte_idx..],
+                    "the end of the boarding pass",
+                ))
+            }
         }
+        count += 1;
     }
 
     if count != 10 {
-        return Err(format!(
-            "Incorrect number of characters. Got {}, expected 10",
-            count
-        ));
+        return Err(ParseError::end_of_input(boarding_pass, "10 characters"));
     }
 
     Ok(seat_id)
 }
 
+// The number of seats there are IDs for, as a boarding pass picks one with 10 binary choices
+const SEATS: u32 = 1 << 10;
+
+// The lowest and highest seat IDs seen so far, along with enough to find a single missing seat between them.  Which
+// seats have been seen is kept too, as a seat seen twice can make it look like the wrong one is missing.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct SeatScan {
+    range: Option<(u32, u32)>,
+    count: u32,
+    sum: u64,
+    seen: [u64; SEATS as usize / 64],
+    repeated: bool,
+}
+
+impl SeatScan {
+    pub fn push(&mut self, seat_id: u32) {
+        if seat_id < SEATS {
+            let (word, bit) = ((seat_id / 64) as usize, 1 << (seat_id % 64));
+            self.repeated |= self.seen[word] & bit != 0;
+            self.seen[word] |= bit;
+        }
+
+        self.range = match self.range {
+            Some((min, max)) => Some((min.min(seat_id), max.max(seat_id))),
+            None => Some((seat_id, seat_id)),
+        };
+        self.count += 1;
+        self.sum += seat_id as u64;
+    }
+
+    pub fn max(&self) -> Option<u32> {
+        self.range.map(|(_, max)| max)
+    }
+
+    // The only seat between the lowest and highest which hasn't been seen, if exactly one seat is missing and none
+    // were seen twice
+    pub fn missing(&self) -> Option<u32> {
+        let (min, max) = self.range?;
+        if self.repeated || self.count != max - min {
+            return None;
+        }
+
+        // a + (a + 1) + ... + b = (a + b) * (b - a + 1) / 2
+        let arithmetic_sum = (min + max) as u64 * (max - min + 1) as u64 / 2;
+
+        arithmetic_sum
+            .checked_sub(self.sum)
+            .map(|missing| missing as u32)
+    }
+
+    fn trace(&self) {
+        trace::summary("seats", |fields| {
+            fields
+                .with("lowest", self.range.map(|(min, _)| min))
+                .with("highest", self.max())
+                .with("count", self.count)
+                .with("missing", self.missing())
+        });
+    }
+}
+
+impl FromIterator<u32> for SeatScan {
+    fn from_iter<I: IntoIterator<Item = u32>>(seat_ids: I) -> Self {
+        let mut scan = SeatScan::default();
+        for seat_id in seat_ids {
+            scan.push(seat_id);
+        }
+
+        scan
+    }
+}
+
+pub fn missing_seat_id(seat_ids: &[u32]) -> Option<u32> {
+    let scan = seat_ids.iter().copied().collect::<SeatScan>();
+    scan.trace();
+
+    scan.missing()
+}
+
+pub struct Day5;
+
+impl Streaming for Day5 {
+    fn solve_stream(reader: impl BufRead) -> Result<(Answer, Answer), StreamError> {
+        let mut scan = SeatScan::default();
+        for seat_id in stream::parse_lines(reader, compute_seat_id) {
+            scan.push(seat_id?);
+        }
+        scan.trace();
+
+        Ok((scan.max().into(), scan.missing().into()))
+    }
+}
+
+impl Solution for Day5 {
+    type Input = Vec<u32>;
+
+    const DAY: u8 = 5;
+
+    fn parse(input: &str) -> Result<Vec<u32>, ParseError> {
+        parse_lines(input, compute_seat_id)
+    }
+
+    fn part1(seat_ids: &Vec<u32>) -> Answer {
+        let highest = seat_ids.iter().max().copied();
+        if let Some(seat_id) = highest {
+            trace::summary("highest", |fields| {
+                fields
+                    .with("seat", seat_id)
+                    .with("pass", boarding_pass(seat_id))
+            });
+        }
+
+        highest.into()
+    }
+
+    fn part2(seat_ids: &Vec<u32>) -> Answer {
+        missing_seat_id(seat_ids).into()
+    }
+}
+
+// The boarding pass for a seat, with the row in the first seven characters and the column in the last three
+pub fn boarding_pass(seat_id: u32) -> String {
+    (0..10)
+        .rev()
+        .map(|bit| match (bit, seat_id >> bit & 1) {
+            (3..=9, 1) => 'B',
+            (3..=9, _) => 'F',
+            (_, 1) => 'R',
+            _ => 'L',
+        })
+        .collect()
+}
+
+impl Generator for Day5 {
+    const DEFAULT_SIZE: usize = 850;
+
+    // A run of seats with one missing from the middle, where the size is the length of the run
+    fn generate(rng: &mut StdRng, size: usize) -> Generated {
+        let size = size.clamp(3, 1024) as u32;
+        let first = rng.gen_range(0..=1024 - size);
+        let last = first + size - 1;
+        let missing = rng.gen_range(first + 1..last);
+
+        let mut passes: Vec<String> = (first..=last)
+            .filter(|&seat_id| seat_id != missing)
+            .map(boarding_pass)
+            .collect();
+        passes.shuffle(rng);
+
+        Generated::new(passes.join("\n"))
+            .part_1(last)
+            .part_2(missing)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_seat_id() {
-        assert_eq!(compute_seat_id(&"BFFFBBFRRR".to_string()), Ok(567));
-        assert_eq!(compute_seat_id(&"FFFBBBFRRR".to_string()), Ok(119));
-        assert_eq!(compute_seat_id(&"BBFFBBFRLL".to_string()), Ok(820));
+        assert_eq!(compute_seat_id("BFFFBBFRRR"), Ok(567));
+        assert_eq!(compute_seat_id("FFFBBBFRRR"), Ok(119));
+        assert_eq!(compute_seat_id("BBFFBBFRLL"), Ok(820));
+    }
+
+    #[test]
+    fn test_boarding_pass() {
+        assert_eq!(boarding_pass(567), "BFFFBBFRRR");
+        assert_eq!(compute_seat_id(&boarding_pass(0)), Ok(0));
+        assert_eq!(compute_seat_id(&boarding_pass(1023)), Ok(1023));
+    }
+
+    #[test]
+    fn test_stream() {
+        let passes = [100, 103, 101, 104].map(boarding_pass).join("\n");
+        let (part_1, part_2) = Day5::solve_stream(passes.as_bytes()).unwrap();
+        assert_eq!((part_1, part_2), (Answer::Number(104), Answer::Number(102)));
+
+        assert_eq!(missing_seat_id(&[7, 8, 9]), None);
+        assert_eq!(missing_seat_id(&[7, 9, 11]), None);
+        assert_eq!(missing_seat_id(&[]), None);
+
+        // Underflows the sum unless repeats are noticed
+        assert_eq!(missing_seat_id(&[0, 4, 4, 4]), None);
+        // Looks like 12 is missing, when it's 10 and 11
+        assert_eq!(missing_seat_id(&[8, 9, 9, 12]), None);
     }
 
     #[test]
     fn test_seat_id_bad_input() {
-        assert_eq!(
-            compute_seat_id(&"BFFFBBFRRRR".to_string()),
-            Err("Incorrect number of characters. Got 11, expected 10".to_string())
-        );
-        assert_eq!(
-            compute_seat_id(&"BFFFBBFRR".to_string()),
-            Err("Incorrect number of characters. Got 9, expected 10".to_string())
-        );
-        assert_eq!(
-            compute_seat_id(&"BFFFBBFRRZ".to_string()),
-            Err("Invalid character: Z".to_string())
-        );
+        let err = compute_seat_id("BFFFBBFRRRR").unwrap_err();
+        assert_eq!(err.column, 11);
+        assert_eq!(err.snippet, "R");
+        assert_eq!(err.expected, "the end of the boarding pass");
+
+        let err = compute_seat_id("BFFFBBFRR").unwrap_err();
+        assert_eq!(err.column, 10);
+        assert_eq!(err.snippet, "");
+        assert_eq!(err.expected, "10 characters");
+
+        let err = compute_seat_id("BFFFBBFRRZ").unwrap_err();
+        assert_eq!(err.column, 10);
+        assert_eq!(err.snippet, "Z");
+        assert_eq!(err.expected, "'L' or 'R'");
+    }
+
+    // Every valid boarding pass has its own seat ID, and every seat ID has a boarding pass
+    proptest! {
+        #[test]
+        fn prop_seat_id_bijection(pass in "[FB]{7}[LR]{3}") {
+            let seat_id = compute_seat_id(&pass).unwrap();
+            prop_assert!(seat_id < 1024);
+            prop_assert_eq!(boarding_pass(seat_id), pass);
+        }
+
+        #[test]
+        fn prop_boarding_pass(seat_id in 0..1024u32) {
+            prop_assert_eq!(compute_seat_id(&boarding_pass(seat_id)), Ok(seat_id));
+        }
     }
 }
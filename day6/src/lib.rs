@@ -0,0 +1,103 @@
+use std::collections::HashSet;
+
+use common::{trace, Answer, ParseError, Solution};
+
+// Each group is the list of answer sets, one per person in the group
+pub type Group = Vec<HashSet<char>>;
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = Vec<Group>;
+
+    const DAY: u8 = 6;
+
+    fn parse(input: &str) -> Result<Vec<Group>, ParseError> {
+        input
+            .split("\n\n")
+            .map(|group| {
+                group
+                    .lines()
+                    .map(|line| {
+                        line.char_indices()
+                            .map(|(idx, c)| match c {
+                                'a'..='z' => Ok(c),
+                                _ => Err(ParseError::new(
+                                    input,
+                                    &line[idx..idx + c.len_utf8()],
+                                    "a question from 'a' to 'z'",
+                                )),
+                            })
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn part1(groups: &Vec<Group>) -> Answer {
+        let mut part_1_sum = 0;
+        for (idx, group) in groups.iter().enumerate() {
+            let anyone = group.iter().flatten().collect::<HashSet<_>>().len();
+            trace::step("group", |fields| {
+                fields.with("number", idx + 1).with("anyone", anyone)
+            });
+            part_1_sum += anyone;
+        }
+        trace::summary("groups", |fields| fields.with("count", groups.len()));
+
+        part_1_sum.into()
+    }
+
+    fn part2(groups: &Vec<Group>) -> Answer {
+        let mut part_2_sum = 0;
+        for (idx, group) in groups.iter().enumerate() {
+            let mut group_answer_sets = group.iter().cloned();
+            if let Some(first_answer_set) = group_answer_sets.next() {
+                let everyone = group_answer_sets
+                    .fold(first_answer_set, |accumulated_intersection, answer_set| {
+                        accumulated_intersection
+                            .intersection(&answer_set)
+                            .cloned()
+                            .collect()
+                    })
+                    .len();
+                trace::step("group", |fields| {
+                    fields.with("number", idx + 1).with("everyone", everyone)
+                });
+                part_2_sum += everyone
+            }
+        }
+        trace::summary("groups", |fields| fields.with("count", groups.len()));
+
+        part_2_sum.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let input = "abc
+
+a
+b
+c
+
+ab
+ac
+
+a
+a
+a
+a
+
+b";
+
+        let groups = Day6::parse(input).unwrap();
+        assert_eq!(Day6::part1(&groups), Answer::Number(11));
+        assert_eq!(Day6::part2(&groups), Answer::Number(6));
+    }
+}
@@ -1,93 +1,109 @@
+use std::collections::HashSet;
 use std::iter::FromIterator;
-use std::{
-    collections::{HashMap, HashSet},
-    str::FromStr,
+
+use common::{
+    error::{parse_lines, parse_number},
+    graph::Graph,
+    trace::{self, Level},
+    Answer, ParseError, Solution,
 };
 
-fn parse_line(line: &str) -> Result<(String, HashSet<(String, i64)>), String> {
-    let mut parent_children = line.split(" bags contain ");
+fn parse_line(line: &str) -> Result<(String, HashSet<(String, i64)>), ParseError> {
+    let (parent_color, child_colors) = line
+        .split_once(" bags contain ")
+        .ok_or_else(|| ParseError::new(line, line, "\"<color> bags contain ...\""))?;
 
-    if let (Some(parent_color), Some(child_colors)) =
-        (parent_children.next(), parent_children.next())
-    {
-        if child_colors == "no other bags." {
-            return Ok((parent_color.to_string(), HashSet::from_iter(vec![])));
-        }
+    if child_colors == "no other bags." {
+        return Ok((parent_color.to_string(), HashSet::from_iter(vec![])));
+    }
 
-        let mut colors: HashSet<(String, i64)> = HashSet::new();
+    let mut colors: HashSet<(String, i64)> = HashSet::new();
 
-        for child_color in child_colors.split(",") {
-            let mut tokens = child_color.split_ascii_whitespace();
-            if let (Some(qty), Some(t1), Some(t2)) = (tokens.next(), tokens.next(), tokens.next()) {
-                if let Ok(qty) = qty.parse::<i64>() {
-                    colors.insert((format!("{} {}", t1, t2), qty));
-                } else {
-                    return Err(format!("Unable to parse string: {}", child_color));
-                }
+    for child_color in child_colors.split(',') {
+        let mut tokens = child_color.split_ascii_whitespace();
+        match (tokens.next(), tokens.next(), tokens.next(), tokens.next()) {
+            (Some(qty), Some(t1), Some(t2), Some("bag" | "bags" | "bag." | "bags.")) => {
+                colors.insert((format!("{} {}", t1, t2), parse_number(line, qty)?));
+            }
+            _ => {
+                return Err(ParseError::new(
+                    line,
+                    child_color.trim(),
+                    "\"<quantity> <color> bags\"",
+                ))
             }
         }
+    }
+
+    Ok((parent_color.to_string(), colors))
+}
+
+pub type Rules = Graph<String, i64>;
 
-        return Ok((parent_color.to_string(), colors));
+pub fn parse_rules(s: &str) -> Result<Rules, ParseError> {
+    let mut graph = Graph::new();
+
+    for (u, children) in parse_lines(s, |line| parse_line(line.trim_end()))? {
+        graph.add_node(u.clone());
+        for (v, wgt) in children {
+            graph.add_edge(u.clone(), v, wgt);
+        }
     }
 
-    Err(format!("Unable to parse string: {}", line))
+    Ok(graph)
 }
 
-pub struct Graph {
-    edge_list: HashMap<String, HashSet<(String, i64)>>,
+// The number of bags inside a bag of the given color
+pub fn subtree_weight(graph: &Rules, root: &str) -> Option<i64> {
+    graph.fold(root, |color, children| {
+        let inside = children
+            .iter()
+            .map(|(weight, inside)| *weight + *weight * inside)
+            .sum();
+        trace::step("bag", |fields| {
+            fields.with("color", color.as_str()).with("inside", inside)
+        });
+        inside
+    })
 }
 
-impl Graph {
-    pub fn new() -> Graph {
-        let edge_list = HashMap::new();
-        Graph { edge_list }
-    }
+pub struct Day7;
 
-    pub fn add_edge(&mut self, u: String, v: String, wgt: i64) {
-        let edge_list = &mut self.edge_list;
-        if !edge_list.contains_key(&u) {
-            edge_list.insert(u.clone(), HashSet::new());
-        }
+impl Solution for Day7 {
+    type Input = Rules;
 
-        let neighbours = edge_list.get_mut(&u).unwrap();
-        neighbours.insert((v.clone(), wgt));
-    }
+    const DAY: u8 = 7;
 
-    pub fn get_neighbours(&self, u: &str) -> Option<HashSet<(String, i64)>> {
-        if let Some(neighbours) = self.edge_list.get(u) {
-            return Some(neighbours.clone());
-        }
-        None
+    fn parse(input: &str) -> Result<Rules, ParseError> {
+        parse_rules(input)
     }
 
-    pub fn reverse_edges(&self) -> Graph {
-        let mut reversed_graph = Graph::new();
-
-        for (u, vs) in self.edge_list.iter() {
-            for (v, w) in vs.iter() {
-                reversed_graph.add_edge(v.clone(), u.clone(), *w);
+    fn part1(graph: &Rules) -> Answer {
+        let reversed = graph.reverse_edges();
+        let containers = reversed.reachable("shiny gold");
+        if trace::enabled(Level::Steps) {
+            let mut colors: Vec<_> = containers.iter().collect();
+            colors.sort();
+            for color in colors {
+                trace::step("container", |fields| fields.with("color", color.as_str()));
             }
         }
+        trace::summary("containers", |fields| {
+            fields.with("count", containers.len())
+        });
 
-        reversed_graph
+        containers.len().into()
     }
-}
-
-impl FromStr for Graph {
-    type Err = String;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut graph = Self::new();
-
-        for line in s.lines() {
-            if let Ok((u, foo)) = parse_line(line.trim()) {
-                for (v, wgt) in foo.iter() {
-                    graph.add_edge(u.clone(), v.clone(), *wgt);
-                }
-            }
+    fn part2(graph: &Rules) -> Answer {
+        // A bag can't hold itself, however deeply nested
+        let inside = subtree_weight(graph, "shiny gold");
+        match inside {
+            Some(inside) => trace::summary("inside", |fields| fields.with("count", inside)),
+            None => trace::summary("cycle", |fields| fields),
         }
 
-        Ok(graph)
+        inside.map_or(Answer::Unsolved, Answer::from)
     }
 }
 
@@ -119,6 +135,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_line_error() {
+        let err = parse_line("light red bags contain 1 bright white bag, x muted yellow bags.")
+            .unwrap_err();
+        assert_eq!(err.column, 44);
+        assert_eq!(err.snippet, "x");
+
+        let err = parse_line("light red bags contain 1 bright white").unwrap_err();
+        assert_eq!(err.column, 24);
+        assert_eq!(err.snippet, "1 bright white");
+    }
+
     #[test]
     fn test_graph_parse() {
         let input = "shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
@@ -127,14 +155,35 @@ vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
 dotted black bags contain no other bags.
 faded blue bags contain no other bags.";
 
-        let graph: Graph = input.parse().unwrap();
+        let graph = parse_rules(input).unwrap();
 
         assert_eq!(
-            graph.get_neighbours(&"shiny gold".to_string()),
-            Some(HashSet::from_iter(vec![
+            graph
+                .neighbours("shiny gold")
+                .map(|(v, wgt)| (v.clone(), *wgt))
+                .collect::<HashSet<_>>(),
+            HashSet::from_iter(vec![
                 ("dark olive".to_string(), 1),
                 ("vibrant plum".to_string(), 2)
-            ]))
-        )
+            ])
+        );
+        assert_eq!(subtree_weight(&graph, "shiny gold"), Some(32));
+    }
+
+    #[test]
+    fn test_example() {
+        let input = "light red bags contain 1 bright white bag, 2 muted yellow bags.
+dark orange bags contain 3 bright white bags, 4 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
+dark olive bags contain 3 faded blue bags, 4 dotted black bags.
+vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
+faded blue bags contain no other bags.
+dotted black bags contain no other bags.";
+
+        let graph = Day7::parse(input).unwrap();
+        assert_eq!(Day7::part1(&graph), Answer::Number(4));
+        assert_eq!(Day7::part2(&graph), Answer::Number(32));
     }
 }
@@ -1,5 +1,10 @@
 use std::{collections::HashSet, str::FromStr};
 
+use common::{
+    error::{parse_lines, parse_number},
+    trace, Answer, ParseError, Solution,
+};
+
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 pub enum Instruction {
     Acc(i64),
@@ -8,33 +13,16 @@ pub enum Instruction {
 }
 
 impl FromStr for Instruction {
-    type Err = String;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let tokens: Vec<_> = s.trim().split_ascii_whitespace().collect();
+        let tokens: Vec<_> = s.split_ascii_whitespace().collect();
         match tokens[..] {
-            ["nop", nop] => {
-                if let Ok(v) = nop.parse::<i64>() {
-                    Ok(Instruction::Nop(v))
-                } else {
-                    Err(format!("Unable to parse int: {}", nop))
-                }
-            },
-            ["acc", val] => {
-                if let Ok(v) = val.parse::<i64>() {
-                    Ok(Instruction::Acc(v))
-                } else {
-                    Err(format!("Unable to parse int: {}", val))
-                }
-            }
-            ["jmp", offset] => {
-                if let Ok(offset) = offset.parse::<i64>() {
-                    Ok(Instruction::Jmp(offset))
-                } else {
-                    Err(format!("Unable to parse int: {}", offset))
-                }
-            }
-            _ => Err(format!("Invalid instruction: {}", s)),
+            ["nop", arg] => Ok(Instruction::Nop(parse_number(s, arg)?)),
+            ["acc", arg] => Ok(Instruction::Acc(parse_number(s, arg)?)),
+            ["jmp", arg] => Ok(Instruction::Jmp(parse_number(s, arg)?)),
+            [op, _] => Err(ParseError::new(s, op, "\"nop\", \"acc\" or \"jmp\"")),
+            _ => Err(ParseError::new(s, s, "an operation and an argument")),
         }
     }
 }
@@ -48,6 +36,7 @@ pub enum MachineError {
     InfiniteLoop
 }
 
+#[derive(Default)]
 pub struct Machine {
     pc: i64,
     acc: i64,
@@ -116,6 +105,66 @@ impl Machine {
     }
 }
 
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = Program;
+
+    const DAY: u8 = 8;
+
+    fn parse(input: &str) -> Result<Program, ParseError> {
+        parse_lines(input, str::parse)
+    }
+
+    fn part1(program: &Program) -> Answer {
+        let mut machine = Machine::new();
+        machine.load(program);
+
+        let _ = machine.run();
+        trace::summary("infinite loop", |fields| {
+            fields.with("pc", machine.pc()).with("acc", machine.acc())
+        });
+
+        machine.acc().into()
+    }
+
+    fn part2(program: &Program) -> Answer {
+        let mut machine = Machine::new();
+        for (idx, instruction) in program.iter().enumerate().skip(1) {
+            let new_instruction = match instruction {
+                Instruction::Jmp(v) => Instruction::Nop(*v),
+                Instruction::Nop(v) => Instruction::Jmp(*v),
+                _ => continue,
+            };
+            let mut new_program = program.clone();
+            if let Some(instruction) = new_program.get_mut(idx) {
+                *instruction = new_instruction;
+            }
+            machine.load(&new_program);
+            let result = machine.run();
+            trace::step("flip", |fields| {
+                fields
+                    .with("pc", idx)
+                    .with("from", format!("{:?}", instruction))
+                    .with("to", format!("{:?}", new_instruction))
+                    .with("outcome", format!("{:?}", result))
+            });
+            if let Ok(acc) = result {
+                trace::summary("repaired", |fields| {
+                    fields
+                        .with("pc", idx)
+                        .with("from", format!("{:?}", instruction))
+                        .with("to", format!("{:?}", new_instruction))
+                        .with("acc", acc)
+                });
+                return acc.into();
+            }
+        }
+
+        Answer::Unsolved
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -129,6 +178,15 @@ mod tests {
         assert_eq!("jmp -3".parse::<Instruction>(), Ok(Instruction::Jmp(-3)));
     }
 
+    #[test]
+    fn test_parse_instruction_error() {
+        let err = "jmp +x".parse::<Instruction>().unwrap_err();
+        assert_eq!((err.column, err.snippet.as_str()), (5, "+x"));
+
+        let err = "mul +2".parse::<Instruction>().unwrap_err();
+        assert_eq!((err.column, err.snippet.as_str()), (1, "mul"));
+    }
+
     #[test]
     fn test_run_without_program() {
         let mut machine = Machine::new();
@@ -1,8 +1,23 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::{
+    cmp::{max, min},
+    collections::{HashMap, VecDeque},
+    io::BufRead,
+};
+
+use common::{
+    error::{parse_lines, parse_number},
+    generate::{Generated, Generator, Rng, SliceRandom, StdRng},
+    stream::{self, StreamError, Streaming},
+    trace, Answer, ParseError, Solution,
+};
+
+pub const PREAMBLE_SIZE: usize = 25;
 
 pub struct Preamble {
     counter: HashMap<i64, usize>,
-    sum_deque: VecDeque<(i64, HashSet<i64>)>,
+    // Each value in the window along with its sums with the values after it, one per pair so that they can be
+    // counted off again
+    sum_deque: VecDeque<(i64, Vec<i64>)>,
 }
 
 impl Preamble {
@@ -10,11 +25,11 @@ impl Preamble {
         let len = seed.len();
         let mut counter: HashMap<_, _> = HashMap::new();
         let mut sum_deque: VecDeque<_> = VecDeque::new();
-        for i in 0..=len - 1 {
-            let mut sums: HashSet<_> = HashSet::new();
-            for j in i + 1..=len - 1 {
+        for i in 0..len {
+            let mut sums = Vec::new();
+            for j in i + 1..len {
                 let x = seed[i] + seed[j];
-                sums.insert(x);
+                sums.push(x);
                 *counter.entry(x).or_insert(0) += 1;
             }
             sum_deque.push_back((seed[i], sums));
@@ -39,17 +54,238 @@ impl Preamble {
 
         for (value, sums) in self.sum_deque.iter_mut() {
             let sum = *value + x;
-            sums.insert(sum);
+            sums.push(sum);
             *self.counter.entry(sum).or_insert(0) += 1;
         }
 
-        self.sum_deque.push_back((x, HashSet::new()));
+        self.sum_deque.push_back((x, Vec::new()));
+    }
+}
+
+// Checks numbers as they arrive, holding on to no more than the window of numbers before the latest one
+pub struct Validator {
+    preamble_size: usize,
+    seed: Vec<i64>,
+    preamble: Option<Preamble>,
+}
+
+impl Validator {
+    pub fn new(preamble_size: usize) -> Validator {
+        Validator {
+            preamble_size,
+            seed: Vec::with_capacity(preamble_size),
+            preamble: None,
+        }
+    }
+
+    // Whether `x` is valid, either as part of the preamble or as the sum of two of the numbers before it
+    pub fn push(&mut self, x: i64) -> bool {
+        match &mut self.preamble {
+            Some(preamble) if !preamble.is_valid(x) => false,
+            Some(preamble) => {
+                preamble.push(x);
+                true
+            }
+            None => {
+                self.seed.push(x);
+                if self.seed.len() == self.preamble_size {
+                    self.preamble = Some(Preamble::new(&self.seed));
+                    self.seed = Vec::new();
+                }
+                true
+            }
+        }
+    }
+}
+
+fn first_invalid_idx(input: &[i64], preamble_size: usize) -> Option<usize> {
+    let mut validator = Validator::new(preamble_size);
+    let (idx, x) = input
+        .iter()
+        .copied()
+        .enumerate()
+        .find(|(_, x)| !validator.push(*x))?;
+    trace::summary("invalid", |fields| {
+        fields.with("line", idx + 1).with("value", x)
+    });
+
+    Some(idx)
+}
+
+pub fn first_invalid(input: &[i64], preamble_size: usize) -> Option<i64> {
+    first_invalid_idx(input, preamble_size).map(|idx| input[idx])
+}
+
+// The sum of the smallest and largest numbers of a run of at least two of them summing to `first_invalid`, if there
+// is one.  The run is looked for by growing and shrinking a window, which only finds it among positive numbers.
+pub fn encryption_weakness(input: &[i64], first_invalid: i64) -> Option<i64> {
+    if input.is_empty() {
+        return None;
+    }
+
+    // The run is `input[i..=j]`
+    let (mut i, mut j) = (0, 0);
+    let mut s = input[0] as i128;
+    while j == i || s != first_invalid as i128 {
+        if j == i || s < first_invalid as i128 {
+            j += 1;
+            s += *input.get(j)? as i128;
+        } else {
+            s -= input[i] as i128;
+            i += 1;
+        }
+    }
+
+    let mut x = input[i];
+    let mut y = input[i];
+
+    for z in input[i + 1..=j].iter() {
+        x = min(x, *z);
+        y = max(y, *z);
+    }
+    trace::summary("range", |fields| {
+        fields
+            .with("first_line", i + 1)
+            .with("last_line", j + 1)
+            .with("smallest", x)
+            .with("largest", y)
+    });
+
+    x.checked_add(y)
+}
+
+// The encryption weakness of the run before the first invalid number, if there is one
+pub fn find_weakness(input: &[i64], preamble_size: usize) -> Option<i64> {
+    let idx = first_invalid_idx(input, preamble_size)?;
+    encryption_weakness(&input[..idx], input[idx])
+}
+
+pub struct Day9;
+
+// Reads numbers until the first invalid one, without holding on to any more of them than the window
+pub fn first_invalid_in_stream(
+    reader: impl BufRead,
+    preamble_size: usize,
+) -> Result<Option<i64>, StreamError> {
+    let mut validator = Validator::new(preamble_size);
+    for (idx, x) in stream::parse_lines(reader, |line| parse_number(line, line)).enumerate() {
+        let x = x?;
+        if !validator.push(x) {
+            trace::summary("invalid", |fields| {
+                fields.with("line", idx + 1).with("value", x)
+            });
+            return Ok(Some(x));
+        }
+    }
+
+    Ok(None)
+}
+
+impl Streaming for Day9 {
+    // The encryption weakness can be anywhere before the first invalid number, so isn't found
+    fn solve_stream(reader: impl BufRead) -> Result<(Answer, Answer), StreamError> {
+        let first_invalid = first_invalid_in_stream(reader, PREAMBLE_SIZE)?;
+
+        Ok((first_invalid.into(), Answer::Unsolved))
+    }
+}
+
+impl Solution for Day9 {
+    type Input = Vec<i64>;
+
+    const DAY: u8 = 9;
+
+    fn parse(input: &str) -> Result<Vec<i64>, ParseError> {
+        parse_lines(input, |line| parse_number(line, line))
+    }
+
+    fn part1(input: &Vec<i64>) -> Answer {
+        first_invalid(input, PREAMBLE_SIZE).into()
+    }
+
+    fn part2(input: &Vec<i64>) -> Answer {
+        find_weakness(input, PREAMBLE_SIZE).into()
+    }
+}
+
+// The number of runs of at least two numbers summing to `target`
+fn runs_summing_to(numbers: &[i64], target: i64) -> usize {
+    (0..numbers.len())
+        .map(|i| {
+            numbers[i + 1..]
+                .iter()
+                .scan(numbers[i], |sum, x| {
+                    *sum += x;
+                    Some(*sum)
+                })
+                .take_while(|&sum| sum <= target)
+                .filter(|&sum| sum == target)
+                .count()
+        })
+        .sum()
+}
+
+impl Generator for Day9 {
+    const DEFAULT_SIZE: usize = 1000;
+
+    // Each number is the sum of two of the smallest few in the window before it, which keeps the numbers from growing
+    // so quickly that they overflow, up to a size of 1200.  The planted invalid number is the sum of a run covering
+    // its whole window, so that it's larger than any pair from the window, and that run is only known to be the
+    // encryption weakness if no other run has the same sum.
+    fn generate(rng: &mut StdRng, size: usize) -> Generated {
+        let size = size.clamp(PREAMBLE_SIZE + 1, 1200);
+        let invalid_at = rng.gen_range(PREAMBLE_SIZE.max(size / 2)..size);
+
+        let mut numbers: Vec<i64> = (1..=2 * PREAMBLE_SIZE as i64).collect();
+        numbers.shuffle(rng);
+        numbers.truncate(PREAMBLE_SIZE);
+
+        let mut run = 0..0;
+        while numbers.len() < size {
+            let n = numbers.len();
+            if n == invalid_at {
+                run = rng.gen_range(n.saturating_sub(PREAMBLE_SIZE + 5)..=n - PREAMBLE_SIZE)..n;
+                numbers.push(numbers[run.clone()].iter().sum());
+                continue;
+            }
+
+            let mut window = numbers[n - PREAMBLE_SIZE..].to_vec();
+            window.sort_unstable();
+            window.dedup();
+            window.truncate(5);
+
+            let x = *window.choose(rng).unwrap();
+            let y = loop {
+                let y = *window.choose(rng).unwrap();
+                if y != x {
+                    break y;
+                }
+            };
+            numbers.push(x + y);
+        }
+
+        let input = numbers
+            .iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let invalid = numbers[invalid_at];
+        let generated = Generated::new(input).part_1(invalid);
+
+        if runs_summing_to(&numbers, invalid) == 1 {
+            let run = &numbers[run];
+            generated.part_2(run.iter().min().unwrap() + run.iter().max().unwrap())
+        } else {
+            generated
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+    use std::collections::HashSet;
 
     #[test]
     fn test_preamble() {
@@ -74,4 +310,70 @@ mod tests {
         assert!(preamble.is_valid(64));
         assert!(preamble.is_valid(66));
     }
+
+    #[test]
+    fn test_example() {
+        let input = [
+            35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117, 150, 182, 127, 219, 299, 277, 309,
+            576,
+        ];
+
+        assert_eq!(first_invalid(&input, 5), Some(127));
+        assert_eq!(first_invalid(&input[..3], 5), None);
+        assert_eq!(encryption_weakness(&input, 127), Some(62));
+        assert_eq!(find_weakness(&input, 5), Some(62));
+    }
+
+    #[test]
+    fn test_no_weakness() {
+        assert_eq!(find_weakness(&[1, 2, -5], 2), None);
+        assert_eq!(find_weakness(&[1, 2, 10], 2), None);
+        assert_eq!(encryption_weakness(&[], 3), None);
+        assert_eq!(encryption_weakness(&[3], 3), None);
+        assert_eq!(encryption_weakness(&[1, 3, 5], 3), None);
+    }
+
+    // The sums of two different entries of the window
+    fn brute_force_sums(window: &[i64]) -> HashSet<i64> {
+        (0..window.len())
+            .flat_map(|i| (i + 1..window.len()).map(move |j| window[i] + window[j]))
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn prop_push(
+            seed in prop::collection::vec(-50..50i64, 2..30),
+            pushed in prop::collection::vec(-50..50i64, 0..40),
+            probes in prop::collection::vec(-100..100i64, 10),
+        ) {
+            let mut window = seed.clone();
+            let mut preamble = Preamble::new(&seed);
+
+            for x in pushed {
+                preamble.push(x);
+                window.remove(0);
+                window.push(x);
+
+                let sums = brute_force_sums(&window);
+                prop_assert_eq!(&preamble.counter.keys().copied().collect::<HashSet<_>>(), &sums);
+                for probe in probes.iter() {
+                    prop_assert_eq!(preamble.is_valid(*probe), sums.contains(probe));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_stream() {
+        let mut input: Vec<String> = (1..=25).map(|x| x.to_string()).collect();
+        input.extend(
+            ["26", "49", "100", "not read"]
+                .iter()
+                .map(|x| x.to_string()),
+        );
+
+        let (part_1, part_2) = Day9::solve_stream(input.join("\n").as_bytes()).unwrap();
+        assert_eq!((part_1, part_2), (Answer::Number(100), Answer::Unsolved));
+    }
 }
@@ -1,64 +1,31 @@
-use std::{
-    cmp::{max, min},
-    env,
-    io::{self, Read},
+use common::{
+    input::InputSource,
+    solution::{main_with_flags, Flag, Solver},
+    Answer, Solution,
 };
-
-use day9::Preamble;
-
-fn main() -> io::Result<()> {
-    let args: Vec<String> = env::args().collect();
-    let preamble_size: usize = args[1].parse().unwrap();
-
-    let mut buffer = String::new();
-    io::stdin().read_to_string(&mut buffer)?;
-
-    let input: Vec<i64> = buffer.lines().map(|s| s.parse::<i64>().unwrap()).collect();
-
-    let (seed, rest) = input.split_at(preamble_size);
-
-    let mut preamble = Preamble::new(seed);
-
-    let mut first_invalid: Option<i64> = None;
-
-    // Part 1
-    for x in rest {
-        if !preamble.is_valid(*x) {
-            println!("{}", *x);
-            first_invalid = Some(*x);
-            break;
-        }
-        preamble.push(*x);
-    }
-
-    // Part 2
-    if let Some(first_invalid) = first_invalid {
-        let mut i = 0;
-        let mut j = 1;
-
-        let mut s = input[0] + input[1];
-        while s != first_invalid {
-            if s < first_invalid {
-                j += 1;
-                s += input[j];
-            } else {
-                s -= input[i];
-                i += 1;
-            }
-        }
-
-        let mut x = input[i];
-        let mut y = input[i];
-
-        for z in input[i + 1..=j].iter() {
-            x = min(x, *z);
-            y = max(y, *z);
-        }
-
-        println!("{}", x + y);
-    } else {
-        panic!("No invalid number found")
-    }
-
-    Ok(())
+use day9::{find_weakness, first_invalid, first_invalid_in_stream, Day9, PREAMBLE_SIZE};
+
+fn main() {
+    let source = InputSource::new(Day9::DAY).embedded(include_str!("../input"));
+    let flags = &[Flag {
+        name: "--preamble",
+        value: "N",
+    }];
+
+    main_with_flags::<Day9>(source, flags, |flags| {
+        let preamble_size = match flags.get("--preamble") {
+            Ok(None) => PREAMBLE_SIZE,
+            Ok(Some(size)) if size >= 2 => size,
+            _ => return Err("Expected a preamble size of at least 2".to_string()),
+        };
+
+        Ok(Solver {
+            part1: Box::new(move |input| first_invalid(input, preamble_size).into()),
+            part2: Box::new(move |input| find_weakness(input, preamble_size).into()),
+            stream: Box::new(move |reader| {
+                let first_invalid = first_invalid_in_stream(reader, preamble_size)?;
+                Ok((first_invalid.into(), Answer::Unsolved))
+            }),
+        })
+    });
 }